@@ -1,11 +1,47 @@
 use anchor_lang::prelude::*;
 use anchor_lang::system_program;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_2022::spl_token_2022::{
+    self,
+    extension::{transfer_fee::TransferFeeConfig, BaseStateWithExtensions, StateWithExtensions},
+};
+use anchor_spl::token_2022_extensions::transfer_fee::{
+    harvest_withheld_tokens_to_mint, HarvestWithheldTokensToMint,
+};
+use anchor_spl::token_interface::{
+    self, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
 use sha2::{Sha256, Digest};
 
 declare_id!("xXBP5XebxLWY2bG3691JeTbCRmcjjncAm5n7jMvVevm");
 
-// 🔑 REPLACE THIS WITH YOUR ACTUAL WALLET ADDRESS
-const PLATFORM_AUTHORITY: &str = "CMvVjcRz1CfmbLJ2RRUsDBYXh4bRcWttpkNY7FREHLUK";
+/// Upper bound on milestones per escrow (keeps account size and tx size sane)
+pub const MAX_MILESTONES: usize = 20;
+
+/// Platform fees are expressed in basis points of a milestone payout, capped
+/// at 10%
+pub const MAX_FEE_BPS: u16 = 1_000;
+
+/// Basis points in a whole (100%)
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+/// Upper bound on platform multisig owners (keeps the multisig account fixed-size)
+pub const MAX_MULTISIG_OWNERS: usize = 10;
+
+/// Layout version written to new escrows. Version 0 is the original fixed
+/// three-milestone layout, which has no version byte at all.
+pub const ESCROW_VERSION: u8 = 1;
+
+/// Review period given to escrows migrated from the version 0 layout (7 days)
+pub const DEFAULT_REVIEW_PERIOD: i64 = 7 * 24 * 60 * 60;
+
+/// How long an application bond stays locked if the recruiter neither rejects
+/// nor selects the applicant (30 days)
+pub const APPLICATION_BOND_LOCK: i64 = 30 * 24 * 60 * 60;
+
+/// Upper bound on mints accepted for token application bonds (keeps the
+/// config account fixed-size)
+pub const MAX_BOND_MINTS: usize = 8;
 
 /// Hash job_id to create a 32-byte seed for PDA (matches frontend implementation)
 fn hash_job_id(job_id: &str) -> [u8; 32] {
@@ -15,320 +51,6002 @@ fn hash_job_id(job_id: &str) -> [u8; 32] {
     hasher.finalize().into()
 }
 
+/// Validates milestone amounts and returns their total
+fn total_milestone_amount(milestone_amounts: &[u64]) -> Result<u64> {
+    require!(
+        !milestone_amounts.is_empty() && milestone_amounts.len() <= MAX_MILESTONES,
+        ErrorCode::InvalidMilestoneCount
+    );
+    require!(
+        milestone_amounts.iter().all(|&amount| amount > 0),
+        ErrorCode::InvalidMilestoneAmount
+    );
+
+    milestone_amounts
+        .iter()
+        .try_fold(0u64, |acc, &amount| acc.checked_add(amount))
+        .ok_or_else(|| error!(ErrorCode::ArithmeticOverflow))
+}
+
+/// Validates per-milestone due dates (0 = no deadline) against the milestone list
+fn validate_due_dates(milestone_due_dates: &[i64], milestone_count: usize) -> Result<()> {
+    require!(
+        milestone_due_dates.len() == milestone_count,
+        ErrorCode::InvalidDueDate
+    );
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        milestone_due_dates.iter().all(|&due| due == 0 || due > now),
+        ErrorCode::InvalidDueDate
+    );
+    Ok(())
+}
+
+/// Fee a Token-2022 mint withholds on a transfer that must deliver `net_amount`.
+/// Returns 0 for legacy SPL mints and Token-2022 mints without a transfer fee.
+fn inverse_transfer_fee(mint: &AccountInfo, net_amount: u64) -> Result<u64> {
+    if *mint.owner != spl_token_2022::ID {
+        return Ok(0);
+    }
+
+    let mint_data = mint.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+    let Ok(fee_config) = mint_state.get_extension::<TransferFeeConfig>() else {
+        return Ok(0);
+    };
+
+    fee_config
+        .calculate_inverse_epoch_fee(Clock::get()?.epoch, net_amount)
+        .ok_or_else(|| error!(ErrorCode::ArithmeticOverflow))
+}
+
+/// Sweeps Token-2022 transfer fees withheld on `account` into its mint.
+/// `CloseAccount` fails while withheld fees remain, so token accounts of a
+/// transfer-fee mint must be harvested before closing. Harvesting is
+/// permissionless; it is a no-op for other mints.
+fn harvest_withheld_fees<'info>(
+    mint: &InterfaceAccount<'info, Mint>,
+    account: &InterfaceAccount<'info, TokenAccount>,
+    token_program: &Interface<'info, TokenInterface>,
+) -> Result<()> {
+    let mint_info = mint.to_account_info();
+    if *mint_info.owner != spl_token_2022::ID {
+        return Ok(());
+    }
+    {
+        let mint_data = mint_info.try_borrow_data()?;
+        let mint_state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+        if mint_state.get_extension::<TransferFeeConfig>().is_err() {
+            return Ok(());
+        }
+    }
+
+    harvest_withheld_tokens_to_mint(
+        CpiContext::new(
+            token_program.to_account_info(),
+            HarvestWithheldTokensToMint {
+                token_program_id: token_program.to_account_info(),
+                mint: mint_info,
+            },
+        ),
+        vec![account.to_account_info()],
+    )
+}
+
+/// Rent-exempt minimum for a SOL vault. It stays in the vault until the
+/// escrow is closed, so the balance above it is exactly the escrowed funds.
+fn vault_reserve() -> Result<u64> {
+    Ok(Rent::get()?.minimum_balance(0))
+}
+
+/// Rewrites an escrow still stored in the version 0 layout in place. The
+/// payer covers the extra rent and the vault reserve, and the unclaimed funds
+/// move from the escrow account into the SOL vault. Does nothing for escrows
+/// already in the current layout, so handlers that call it first accept both
+/// layouts during the rollout.
+fn upgrade_legacy_escrow<'info>(
+    escrow: &mut Escrow,
+    escrow_info: &AccountInfo<'info>,
+    vault: &SystemAccount<'info>,
+    vault_bump: u8,
+    payer: &AccountInfo<'info>,
+    system_program: &Program<'info, System>,
+) -> Result<()> {
+    if escrow.version != 0 {
+        return Ok(());
+    }
+
+    let outstanding = escrow.outstanding_amount()?;
+    let escrow_rent = escrow_info
+        .lamports()
+        .checked_sub(outstanding)
+        .ok_or(ErrorCode::InsufficientEscrowBalance)?;
+    let new_space = Escrow::space(escrow.milestone_amounts.len());
+    let rent_due = Rent::get()?
+        .minimum_balance(new_space)
+        .saturating_sub(escrow_rent);
+    if rent_due > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                system_program.to_account_info(),
+                system_program::Transfer {
+                    from: payer.clone(),
+                    to: escrow_info.clone(),
+                },
+            ),
+            rent_due,
+        )?;
+    }
+    system_program::transfer(
+        CpiContext::new(
+            system_program.to_account_info(),
+            system_program::Transfer {
+                from: payer.clone(),
+                to: vault.to_account_info(),
+            },
+        ),
+        vault_reserve()?,
+    )?;
+    transfer_lamports(escrow_info, &vault.to_account_info(), outstanding)?;
+
+    escrow_info.resize(new_space)?;
+    escrow.version = ESCROW_VERSION;
+    escrow.vault_bump = vault_bump;
+
+    emit!(EscrowMigrated {
+        escrow: escrow_info.key(),
+        job_hash: escrow.job_hash,
+        from_version: 0,
+        to_version: ESCROW_VERSION,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Moves lamports out of an escrow's SOL vault, signing as the vault PDA
+fn transfer_from_vault<'info>(
+    escrow: &Account<'info, Escrow>,
+    vault: &SystemAccount<'info>,
+    to: &AccountInfo<'info>,
+    system_program: &Program<'info, System>,
+    amount: u64,
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+    let escrow_key = escrow.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[b"vault", escrow_key.as_ref(), &[escrow.vault_bump]]];
+
+    system_program::transfer(
+        CpiContext::new_with_signer(
+            system_program.to_account_info(),
+            system_program::Transfer {
+                from: vault.to_account_info(),
+                to: to.clone(),
+            },
+            signer_seeds,
+        ),
+        amount,
+    )
+}
+
+/// Moves lamports out of a program-owned account
+fn transfer_lamports(from: &AccountInfo, to: &AccountInfo, amount: u64) -> Result<()> {
+    let from_balance = from.lamports();
+    **from.try_borrow_mut_lamports()? = from_balance
+        .checked_sub(amount)
+        .ok_or(ErrorCode::InsufficientEscrowBalance)?;
+    let to_balance = to.lamports();
+    **to.try_borrow_mut_lamports()? = to_balance
+        .checked_add(amount)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+    Ok(())
+}
+
+/// Validates an arbitrator's per-milestone ruling and returns the freelancer's
+/// total share. Whatever is not awarded to the freelancer goes back to the recruiter.
+fn freelancer_dispute_award(escrow: &Escrow, freelancer_amounts: &[u64]) -> Result<u64> {
+    require!(
+        freelancer_amounts.len() == escrow.milestone_amounts.len(),
+        ErrorCode::InvalidDisputeRuling
+    );
+
+    let mut total: u64 = 0;
+    for (i, &award) in freelancer_amounts.iter().enumerate() {
+        let outstanding = escrow.milestone_amounts[i] - escrow.milestone_claimed_amounts[i];
+        require!(award <= outstanding, ErrorCode::InvalidDisputeRuling);
+        total = total.checked_add(award).ok_or(ErrorCode::ArithmeticOverflow)?;
+    }
+
+    Ok(total)
+}
+
+/// Platform fee on a payout of `amount`, rounded down
+fn platform_fee(amount: u64, fee_bps: u16) -> Result<u64> {
+    let fee = (amount as u128)
+        .checked_mul(fee_bps as u128)
+        .ok_or(ErrorCode::ArithmeticOverflow)?
+        / BPS_DENOMINATOR as u128;
+    Ok(fee as u64)
+}
+
+/// Validates a multisig owner set (non-empty, bounded, no duplicates) and threshold
+fn validate_multisig_owners(owners: &[Pubkey], threshold: u8) -> Result<()> {
+    require!(
+        !owners.is_empty() && owners.len() <= MAX_MULTISIG_OWNERS,
+        ErrorCode::InvalidMultisigOwners
+    );
+    require!(
+        threshold > 0 && threshold as usize <= owners.len(),
+        ErrorCode::InvalidMultisigOwners
+    );
+    for (i, owner) in owners.iter().enumerate() {
+        require!(!owners[..i].contains(owner), ErrorCode::InvalidMultisigOwners);
+    }
+    Ok(())
+}
+
+/// Pays or forfeits the dispute bond, depending on the ruling
+fn settle_dispute_bond<'info>(
+    dispute: &Account<'info, Dispute>,
+    treasury: &AccountInfo<'info>,
+    refund_bond: bool,
+) -> Result<()> {
+    // A refunded bond leaves with the rest of the account when it is closed
+    // to the opener
+    if !refund_bond && dispute.bond > 0 {
+        transfer_lamports(&dispute.to_account_info(), treasury, dispute.bond)?;
+    }
+    Ok(())
+}
+
+/// Moves tokens out of an escrow's vault, signing as the escrow PDA
+fn transfer_from_token_vault<'info>(
+    escrow: &Account<'info, Escrow>,
+    vault: &InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    to: &InterfaceAccount<'info, TokenAccount>,
+    token_program: &Interface<'info, TokenInterface>,
+    amount: u64,
+) -> Result<()> {
+    let generation = escrow.generation_seed();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"escrow",
+        escrow.recruiter.as_ref(),
+        &escrow.job_hash,
+        &generation,
+        &[escrow.bump],
+    ]];
+
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            TransferChecked {
+                from: vault.to_account_info(),
+                mint: mint.to_account_info(),
+                to: to.to_account_info(),
+                authority: escrow.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        amount,
+        mint.decimals,
+    )
+}
+
+/// Harvests an emptied escrow token vault's withheld transfer fees and closes
+/// it, signing as the escrow PDA
+fn close_token_vault<'info>(
+    escrow: &Account<'info, Escrow>,
+    vault: &InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    destination: &AccountInfo<'info>,
+    token_program: &Interface<'info, TokenInterface>,
+) -> Result<()> {
+    harvest_withheld_fees(mint, vault, token_program)?;
+
+    let generation = escrow.generation_seed();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"escrow",
+        escrow.recruiter.as_ref(),
+        &escrow.job_hash,
+        &generation,
+        &[escrow.bump],
+    ]];
+
+    token_interface::close_account(CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        CloseAccount {
+            account: vault.to_account_info(),
+            destination: destination.clone(),
+            authority: escrow.to_account_info(),
+        },
+        signer_seeds,
+    ))
+}
+
+/// Empties an application's token bond vault into `to` and closes it,
+/// signing as the application PDA
+fn drain_bond_vault<'info>(
+    application: &Account<'info, Application>,
+    bond_vault: &InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    to: &InterfaceAccount<'info, TokenAccount>,
+    rent_recipient: &AccountInfo<'info>,
+    token_program: &Interface<'info, TokenInterface>,
+) -> Result<()> {
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"application",
+        application.recruiter.as_ref(),
+        &application.job_hash,
+        application.freelancer.as_ref(),
+        &[application.bump],
+    ]];
+
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            TransferChecked {
+                from: bond_vault.to_account_info(),
+                mint: mint.to_account_info(),
+                to: to.to_account_info(),
+                authority: application.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        bond_vault.amount,
+        mint.decimals,
+    )?;
+    harvest_withheld_fees(mint, bond_vault, token_program)?;
+
+    token_interface::close_account(CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        CloseAccount {
+            account: bond_vault.to_account_info(),
+            destination: rent_recipient.clone(),
+            authority: application.to_account_info(),
+        },
+        signer_seeds,
+    ))
+}
+
 #[program]
 pub mod freelance_platform {
     use super::*;
 
-    /// Creates escrow PDA and locks funds for a job
+    /// Creates escrow PDA and locks funds for a job, plus any advance. The
+    /// escrow stays Pending, and the recruiter can withdraw, until the
+    /// freelancer accepts `terms` with `accept_job`. Each call for the same
+    /// job opens a new escrow at the job counter's next generation.
     pub fn create_job_escrow(
         ctx: Context<CreateJobEscrow>,
         job_id: String,
         freelancer: Pubkey,
-        milestone_amounts: [u64; 3],
+        milestone_amounts: Vec<u64>,
+        milestone_due_dates: Vec<i64>,
+        review_period: i64,
+        terms: JobTerms,
     ) -> Result<()> {
-        require!(job_id.len() <= 50, ErrorCode::JobIdTooLong);
-        require!(
-            milestone_amounts.iter().all(|&amount| amount > 0),
-            ErrorCode::InvalidMilestoneAmount
-        );
+        require!(review_period > 0, ErrorCode::InvalidReviewPeriod);
+        let total_amount = total_milestone_amount(&milestone_amounts)?;
+        validate_due_dates(&milestone_due_dates, milestone_amounts.len())?;
+        terms.validate()?;
+        let reserve = vault_reserve()?;
+        let deposit = total_amount
+            .checked_add(terms.advance)
+            .and_then(|amount| amount.checked_add(reserve))
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
 
-        let total_amount: u64 = milestone_amounts.iter().sum();
-
-        // Transfer SOL from recruiter to escrow PDA
+        // Transfer SOL from recruiter to the escrow's vault PDA, plus the
+        // vault's rent-exempt reserve
         system_program::transfer(
             CpiContext::new(
                 ctx.accounts.system_program.to_account_info(),
                 system_program::Transfer {
                     from: ctx.accounts.recruiter.to_account_info(),
-                    to: ctx.accounts.escrow.to_account_info(),
+                    to: ctx.accounts.vault.to_account_info(),
                 },
             ),
-            total_amount,
+            deposit,
         )?;
 
         let escrow = &mut ctx.accounts.escrow;
+        escrow.version = ESCROW_VERSION;
         escrow.recruiter = ctx.accounts.recruiter.key();
         escrow.freelancer = freelancer;
-        escrow.job_id = job_id;
-        escrow.milestone_amounts = milestone_amounts;
-        escrow.milestones_approved = [false; 3];
-        escrow.milestones_claimed = [false; 3];
+        escrow.job_hash = hash_job_id(&job_id);
+        escrow.set_milestones(milestone_amounts, milestone_due_dates);
+        escrow.review_period = review_period;
+        escrow.mint = None;
+        escrow.terms = terms;
+        escrow.fee_bps = ctx.accounts.config.fee_bps;
+        escrow.generation = Some(ctx.accounts.job_counter.next_generation()?);
+        escrow.collateral = 0;
+        escrow.status = EscrowStatus::Pending;
+        escrow.vault_bump = ctx.bumps.vault;
         escrow.bump = ctx.bumps.escrow;
+        ctx.accounts.job_counter.bump = ctx.bumps.job_counter;
+
+        emit!(EscrowCreated {
+            escrow: escrow.key(),
+            job_id,
+            job_hash: escrow.job_hash,
+            recruiter: escrow.recruiter,
+            freelancer: escrow.freelancer,
+            mint: None,
+            milestone_amounts: escrow.milestone_amounts.clone(),
+            total_amount,
+            terms: escrow.terms.clone(),
+            generation: escrow.generation,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Freelancer accepts a Pending escrow by signing its terms hash before
+    /// the acceptance deadline. The escrow becomes binding (Funded) and any
+    /// advance is paid out, less the platform fee.
+    pub fn accept_job(ctx: Context<AcceptJob>, terms_hash: [u8; 32]) -> Result<()> {
+        let advance = ctx
+            .accounts
+            .escrow
+            .accept(terms_hash, Clock::get()?.unix_timestamp)?;
+        let fee = platform_fee(advance, ctx.accounts.escrow.fee_bps)?;
+
+        transfer_from_vault(
+            &ctx.accounts.escrow,
+            &ctx.accounts.vault,
+            &ctx.accounts.freelancer.to_account_info(),
+            &ctx.accounts.system_program,
+            advance - fee,
+        )?;
+        if fee > 0 {
+            transfer_from_vault(
+                &ctx.accounts.escrow,
+                &ctx.accounts.vault,
+                &ctx.accounts.fee_treasury.to_account_info(),
+                &ctx.accounts.system_program,
+                fee,
+            )?;
+            ctx.accounts.fee_treasury.credit_lamports(fee)?;
+        }
+
+        emit!(JobAccepted {
+            escrow: ctx.accounts.escrow.key(),
+            job_hash: ctx.accounts.escrow.job_hash,
+            freelancer: ctx.accounts.escrow.freelancer,
+            terms_hash,
+            advance,
+            fee,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
 
         Ok(())
     }
 
-    /// Recruiter approves a completed milestone
+    /// Recruiter approves a milestone up to `approved_amount` (cumulative).
+    /// Approving less than the milestone amount accepts the deliverable partially.
     pub fn approve_milestone(
         ctx: Context<ApproveMilestone>,
         milestone_index: u8,
+        approved_amount: u64,
     ) -> Result<()> {
-        require!(milestone_index < 3, ErrorCode::InvalidMilestoneIndex);
+        let escrow_info = ctx.accounts.escrow.to_account_info();
+        upgrade_legacy_escrow(
+            &mut ctx.accounts.escrow,
+            &escrow_info,
+            &ctx.accounts.vault,
+            ctx.bumps.vault,
+            &ctx.accounts.recruiter.to_account_info(),
+            &ctx.accounts.system_program,
+        )?;
 
         let escrow = &mut ctx.accounts.escrow;
+        escrow.require_active()?;
+        let index = escrow.milestone_index(milestone_index)?;
 
         require!(
-            !escrow.milestones_approved[milestone_index as usize],
+            escrow.milestone_amounts[index] > 0,
+            ErrorCode::MilestoneRefunded
+        );
+        require!(
+            !escrow.is_fully_approved(index),
             ErrorCode::MilestoneAlreadyApproved
         );
+        require!(
+            approved_amount > escrow.milestone_approved_amounts[index]
+                && approved_amount <= escrow.milestone_amounts[index],
+            ErrorCode::InvalidApprovalAmount
+        );
 
-        escrow.milestones_approved[milestone_index as usize] = true;
+        escrow.milestone_approved_amounts[index] = approved_amount;
+        // The pending review (if any) has been answered
+        escrow.milestones_submitted_at[index] = 0;
+        escrow.refresh_status();
+
+        emit!(MilestoneApproved {
+            escrow: escrow.key(),
+            job_hash: escrow.job_hash,
+            milestone_index,
+            approved_amount,
+            milestone_amount: escrow.milestone_amounts[index],
+            auto_approved: false,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
 
         Ok(())
     }
 
-    /// Freelancer claims payment for approved milestone
-    pub fn claim_milestone(
-        ctx: Context<ClaimMilestone>,
+    /// Freelancer submits a milestone for review, starting the review window
+    pub fn submit_milestone(
+        ctx: Context<SubmitMilestone>,
         milestone_index: u8,
     ) -> Result<()> {
-        require!(milestone_index < 3, ErrorCode::InvalidMilestoneIndex);
-
         let escrow = &mut ctx.accounts.escrow;
+        escrow.require_active()?;
+        let index = escrow.milestone_index(milestone_index)?;
 
         require!(
-            escrow.milestones_approved[milestone_index as usize],
-            ErrorCode::MilestoneNotApproved
+            escrow.milestone_amounts[index] > 0,
+            ErrorCode::MilestoneRefunded
         );
         require!(
-            !escrow.milestones_claimed[milestone_index as usize],
-            ErrorCode::MilestoneAlreadyClaimed
+            !escrow.is_fully_approved(index),
+            ErrorCode::MilestoneAlreadyApproved
+        );
+        require!(
+            escrow.milestones_submitted_at[index] == 0,
+            ErrorCode::MilestoneAlreadySubmitted
         );
 
-        let amount = escrow.milestone_amounts[milestone_index as usize];
-
-        // Transfer SOL from escrow PDA to freelancer
-        **escrow
-            .to_account_info()
-            .try_borrow_mut_lamports()? -= amount;
-        **ctx
-            .accounts
-            .freelancer
-            .to_account_info()
-            .try_borrow_mut_lamports()? += amount;
+        let now = Clock::get()?.unix_timestamp;
+        escrow.milestones_submitted_at[index] = now;
+        escrow.refresh_status();
 
-        escrow.milestones_claimed[milestone_index as usize] = true;
+        emit!(MilestoneSubmitted {
+            escrow: escrow.key(),
+            job_hash: escrow.job_hash,
+            milestone_index,
+            timestamp: now,
+        });
 
         Ok(())
     }
 
-    /// Cancel job and refund recruiter (only if no milestones approved)
-    pub fn cancel_job(ctx: Context<CancelJob>) -> Result<()> {
-        let escrow = &ctx.accounts.escrow;
+    /// Recruiter rejects a submitted milestone; the freelancer must resubmit
+    pub fn reject_milestone(
+        ctx: Context<RejectMilestone>,
+        milestone_index: u8,
+    ) -> Result<()> {
+        let escrow = &mut ctx.accounts.escrow;
+        escrow.require_active()?;
+        let index = escrow.milestone_index(milestone_index)?;
 
         require!(
-            !escrow.milestones_approved.iter().any(|&approved| approved),
-            ErrorCode::CannotCancelAfterApproval
+            !escrow.is_fully_approved(index),
+            ErrorCode::MilestoneAlreadyApproved
+        );
+        require!(
+            escrow.milestones_submitted_at[index] != 0,
+            ErrorCode::MilestoneNotSubmitted
         );
 
-        // Calculate remaining balance
-        let remaining_balance = escrow
-            .milestone_amounts
-            .iter()
-            .enumerate()
-            .filter(|(i, _)| !escrow.milestones_claimed[*i])
-            .map(|(_, &amount)| amount)
-            .sum::<u64>();
+        escrow.milestones_submitted_at[index] = 0;
 
-        **ctx
-            .accounts
-            .escrow
-            .to_account_info()
-            .try_borrow_mut_lamports()? -= remaining_balance;
-        **ctx
-            .accounts
-            .recruiter
-            .to_account_info()
-            .try_borrow_mut_lamports()? += remaining_balance;
+        emit!(MilestoneRejected {
+            escrow: escrow.key(),
+            job_hash: escrow.job_hash,
+            milestone_index,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
 
         Ok(())
     }
 
-    /// 🔥 NEW: Platform owner can withdraw any amount from escrow
-    /// Use cases: platform fees, dispute resolution, emergency withdrawals
-    pub fn platform_withdraw(
-        ctx: Context<PlatformWithdraw>,
-        amount: u64,
+    /// Anyone can fully approve a submitted milestone once the recruiter has
+    /// let the review window lapse without approving or rejecting it
+    pub fn auto_approve_milestone(
+        ctx: Context<AutoApproveMilestone>,
+        milestone_index: u8,
     ) -> Result<()> {
-        let escrow_balance = ctx.accounts.escrow.to_account_info().lamports();
-        
+        let escrow = &mut ctx.accounts.escrow;
+        escrow.require_active()?;
+        let index = escrow.milestone_index(milestone_index)?;
+
         require!(
-            amount <= escrow_balance,
-            ErrorCode::InsufficientEscrowBalance
+            !escrow.is_fully_approved(index),
+            ErrorCode::MilestoneAlreadyApproved
         );
+        let submitted_at = escrow.milestones_submitted_at[index];
+        require!(submitted_at != 0, ErrorCode::MilestoneNotSubmitted);
 
-        // Transfer from escrow to platform authority
-        **ctx
-            .accounts
-            .escrow
-            .to_account_info()
-            .try_borrow_mut_lamports()? -= amount;
-        **ctx
-            .accounts
-            .platform_authority
-            .to_account_info()
-            .try_borrow_mut_lamports()? += amount;
+        let review_deadline = submitted_at
+            .checked_add(escrow.review_period)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        let now = Clock::get()?.unix_timestamp;
+        require!(now >= review_deadline, ErrorCode::ReviewWindowOpen);
+
+        escrow.milestone_approved_amounts[index] = escrow.milestone_amounts[index];
+        escrow.milestones_submitted_at[index] = 0;
+        escrow.refresh_status();
+
+        emit!(MilestoneApproved {
+            escrow: escrow.key(),
+            job_hash: escrow.job_hash,
+            milestone_index,
+            approved_amount: escrow.milestone_amounts[index],
+            milestone_amount: escrow.milestone_amounts[index],
+            auto_approved: true,
+            timestamp: now,
+        });
 
         Ok(())
     }
 
-    /// 🔥 NEW: Platform owner can withdraw and close escrow completely
-    pub fn platform_emergency_close(
-        ctx: Context<PlatformEmergencyClose>,
+    /// Recruiter refunds the unapproved remainder of a partially approved
+    /// milestone, shrinking the milestone to the approved amount
+    pub fn refund_milestone_remainder(
+        ctx: Context<RefundMilestoneRemainder>,
+        milestone_index: u8,
     ) -> Result<()> {
-        // All remaining funds go to platform authority
-        let escrow_balance = ctx.accounts.escrow.to_account_info().lamports();
+        let escrow = &mut ctx.accounts.escrow;
+        escrow.require_active()?;
+        let index = escrow.milestone_index(milestone_index)?;
 
-        **ctx
-            .accounts
-            .escrow
-            .to_account_info()
-            .try_borrow_mut_lamports()? = 0;
-        **ctx
-            .accounts
-            .platform_authority
-            .to_account_info()
-            .try_borrow_mut_lamports()? += escrow_balance;
+        let refund = escrow.release_unapproved_remainder(index)?;
+        escrow.refresh_status();
+
+        emit!(MilestoneRemainderRefunded {
+            escrow: escrow.key(),
+            job_hash: escrow.job_hash,
+            milestone_index,
+            amount: refund,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        transfer_from_vault(
+            escrow,
+            &ctx.accounts.vault,
+            &ctx.accounts.recruiter.to_account_info(),
+            &ctx.accounts.system_program,
+            refund,
+        )
+    }
+
+    /// Freelancer claims the approved, not yet claimed part of a milestone.
+    /// The platform fee is deducted and credited to the treasury PDA.
+    pub fn claim_milestone(
+        ctx: Context<ClaimMilestone>,
+        milestone_index: u8,
+    ) -> Result<()> {
+        let escrow_info = ctx.accounts.escrow.to_account_info();
+        upgrade_legacy_escrow(
+            &mut ctx.accounts.escrow,
+            &escrow_info,
+            &ctx.accounts.vault,
+            ctx.bumps.vault,
+            &ctx.accounts.freelancer.to_account_info(),
+            &ctx.accounts.system_program,
+        )?;
+
+        let escrow = &mut ctx.accounts.escrow;
+        escrow.require_active()?;
+        let index = escrow.milestone_index(milestone_index)?;
+
+        let amount = escrow.take_claimable(index)?;
+        escrow.refresh_status();
+        let fee = platform_fee(amount, escrow.fee_bps)?;
+
+        let timestamp = Clock::get()?.unix_timestamp;
+        emit!(MilestoneClaimed {
+            escrow: escrow.key(),
+            job_hash: escrow.job_hash,
+            milestone_index,
+            amount,
+            fee,
+            timestamp,
+        });
+
+        // Transfer SOL from the vault to freelancer
+        transfer_from_vault(
+            &ctx.accounts.escrow,
+            &ctx.accounts.vault,
+            &ctx.accounts.freelancer.to_account_info(),
+            &ctx.accounts.system_program,
+            amount - fee,
+        )?;
+
+        if fee > 0 {
+            transfer_from_vault(
+                &ctx.accounts.escrow,
+                &ctx.accounts.vault,
+                &ctx.accounts.fee_treasury.to_account_info(),
+                &ctx.accounts.system_program,
+                fee,
+            )?;
+            ctx.accounts.fee_treasury.credit_lamports(fee)?;
+
+            emit!(FeeCollected {
+                escrow: ctx.accounts.escrow.key(),
+                job_hash: ctx.accounts.escrow.job_hash,
+                mint: None,
+                milestone_index,
+                amount: fee,
+                timestamp,
+            });
+        }
 
         Ok(())
     }
-}
 
-#[derive(Accounts)]
-#[instruction(job_id: String)]
-pub struct CreateJobEscrow<'info> {
-    #[account(
-        init,
-        payer = recruiter,
-        space = 8 + Escrow::INIT_SPACE,
-        seeds = [b"escrow", recruiter.key().as_ref(), &hash_job_id(&job_id)],
-        bump
-    )]
-    pub escrow: Account<'info, Escrow>,
+    /// Cancel job and refund recruiter. Before the freelancer accepts the
+    /// recruiter can do this alone; afterwards the freelancer must co-sign,
+    /// and only while no milestones are approved or submitted. Version 0
+    /// escrows predate acceptance, so their recruiter still cancels alone.
+    /// The escrow stays on-chain as Cancelled until `close_completed_escrow`.
+    pub fn cancel_job(ctx: Context<CancelJob>) -> Result<()> {
+        let escrow_info = ctx.accounts.escrow.to_account_info();
+        upgrade_legacy_escrow(
+            &mut ctx.accounts.escrow,
+            &escrow_info,
+            &ctx.accounts.vault,
+            ctx.bumps.vault,
+            &ctx.accounts.recruiter.to_account_info(),
+            &ctx.accounts.system_program,
+        )?;
 
-    #[account(mut)]
-    pub recruiter: Signer<'info>,
+        let escrow = &mut ctx.accounts.escrow;
 
-    pub system_program: Program<'info, System>,
-}
+        if escrow.status != EscrowStatus::Pending {
+            escrow.require_active()?;
+        }
+        if escrow.is_accepted() {
+            require!(
+                ctx.accounts.freelancer.is_some(),
+                ErrorCode::FreelancerConsentRequired
+            );
+        }
+        escrow.require_cancellable()?;
 
-#[derive(Accounts)]
-pub struct ApproveMilestone<'info> {
-    #[account(
-        mut,
-        seeds = [
-            b"escrow",
-            escrow.recruiter.as_ref(),
-            &hash_job_id(&escrow.job_id)
-        ],
-        bump = escrow.bump,
-        has_one = recruiter
-    )]
-    pub escrow: Account<'info, Escrow>,
+        let remaining_balance = escrow.refundable_amount()?;
+        escrow.status = EscrowStatus::Cancelled;
 
-    pub recruiter: Signer<'info>,
-}
+        transfer_from_vault(
+            escrow,
+            &ctx.accounts.vault,
+            &ctx.accounts.recruiter.to_account_info(),
+            &ctx.accounts.system_program,
+            remaining_balance,
+        )?;
 
-#[derive(Accounts)]
-pub struct ClaimMilestone<'info> {
-    #[account(
-        mut,
-        seeds = [
-            b"escrow",
-            escrow.recruiter.as_ref(),
-            &hash_job_id(&escrow.job_id)
-        ],
-        bump = escrow.bump,
-        has_one = freelancer
-    )]
-    pub escrow: Account<'info, Escrow>,
+        emit!(JobCancelled {
+            escrow: escrow.key(),
+            job_hash: escrow.job_hash,
+            refund_amount: remaining_balance,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
 
-    #[account(mut)]
-    pub freelancer: Signer<'info>,
-}
+        Ok(())
+    }
 
-#[derive(Accounts)]
-pub struct CancelJob<'info> {
-    #[account(
-        mut,
-        seeds = [
-            b"escrow",
-            escrow.recruiter.as_ref(),
-            &hash_job_id(&escrow.job_id)
-        ],
-        bump = escrow.bump,
-        has_one = recruiter,
-        close = recruiter
-    )]
-    pub escrow: Account<'info, Escrow>,
+    /// Refunds a Pending escrow to the recruiter once its acceptance deadline
+    /// has passed. Permissionless.
+    pub fn refund_unaccepted_job(ctx: Context<RefundUnacceptedJob>) -> Result<()> {
+        let escrow = &mut ctx.accounts.escrow;
+        escrow.require_acceptance_expired(Clock::get()?.unix_timestamp)?;
 
-    #[account(mut)]
-    pub recruiter: Signer<'info>,
-}
+        let refund_amount = escrow.refundable_amount()?;
+        escrow.status = EscrowStatus::Cancelled;
 
-// 🔥 NEW: Platform withdrawal context
-#[derive(Accounts)]
-pub struct PlatformWithdraw<'info> {
-    #[account(
-        mut,
-        seeds = [
-            b"escrow",
-            escrow.recruiter.as_ref(),
-            &hash_job_id(&escrow.job_id)
-        ],
-        bump = escrow.bump
-    )]
-    pub escrow: Account<'info, Escrow>,
+        transfer_from_vault(
+            escrow,
+            &ctx.accounts.vault,
+            &ctx.accounts.recruiter.to_account_info(),
+            &ctx.accounts.system_program,
+            refund_amount,
+        )?;
 
-    #[account(
-        mut,
-        address = PLATFORM_AUTHORITY.parse::<Pubkey>().unwrap() @ ErrorCode::UnauthorizedPlatformAccess
-    )]
-    pub platform_authority: Signer<'info>,
-}
+        emit!(JobCancelled {
+            escrow: escrow.key(),
+            job_hash: escrow.job_hash,
+            refund_amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
 
-// 🔥 NEW: Platform emergency close context
-#[derive(Accounts)]
-pub struct PlatformEmergencyClose<'info> {
-    #[account(
-        mut,
-        seeds = [
-            b"escrow",
-            escrow.recruiter.as_ref(),
-            &hash_job_id(&escrow.job_id)
-        ],
-        bump = escrow.bump,
-        close = platform_authority
-    )]
-    pub escrow: Account<'info, Escrow>,
+        Ok(())
+    }
 
-    #[account(
-        mut,
-        address = PLATFORM_AUTHORITY.parse::<Pubkey>().unwrap() @ ErrorCode::UnauthorizedPlatformAccess
-    )]
-    pub platform_authority: Signer<'info>,
-}
+    /// Recruiter and freelancer jointly amend the milestone list: change
+    /// amounts, add or remove milestones, or re-split the total. Milestones
+    /// with any approval are locked. Any difference in the total is taken from
+    /// or refunded to the recruiter.
+    pub fn amend_escrow(
+        ctx: Context<AmendEscrow>,
+        milestone_amounts: Vec<u64>,
+        milestone_due_dates: Vec<i64>,
+    ) -> Result<()> {
+        ctx.accounts.escrow.require_active()?;
 
-#[account]
-#[derive(InitSpace)]
-pub struct Escrow {
-    pub recruiter: Pubkey,              // 32
-    pub freelancer: Pubkey,             // 32
-    #[max_len(50)]
-    pub job_id: String,                 // 4 + 50
-    pub milestone_amounts: [u64; 3],    // 8 * 3
-    pub milestones_approved: [bool; 3], // 1 * 3
-    pub milestones_claimed: [bool; 3],  // 1 * 3
-    pub bump: u8,                       // 1
+        let new_total = ctx.accounts.escrow.amended_total(&milestone_amounts)?;
+        let old_total = ctx.accounts.escrow.total_amount()?;
+        ctx.accounts
+            .escrow
+            .amend_milestones(milestone_amounts, milestone_due_dates)?;
+        ctx.accounts.escrow.refresh_status();
+
+        emit!(EscrowAmended {
+            escrow: ctx.accounts.escrow.key(),
+            job_hash: ctx.accounts.escrow.job_hash,
+            milestone_amounts: ctx.accounts.escrow.milestone_amounts.clone(),
+            previous_total: old_total,
+            new_total,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        if new_total > old_total {
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.recruiter.to_account_info(),
+                        to: ctx.accounts.vault.to_account_info(),
+                    },
+                ),
+                new_total - old_total,
+            )?;
+        } else if new_total < old_total {
+            transfer_from_vault(
+                &ctx.accounts.escrow,
+                &ctx.accounts.vault,
+                &ctx.accounts.recruiter.to_account_info(),
+                &ctx.accounts.system_program,
+                old_total - new_total,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Recruiter adds funds to a live escrow as new milestones (change order).
+    /// Existing approval and claim state is untouched.
+    pub fn add_milestones(
+        ctx: Context<AddMilestones>,
+        milestone_amounts: Vec<u64>,
+        milestone_due_dates: Vec<i64>,
+    ) -> Result<()> {
+        ctx.accounts.escrow.require_active()?;
+
+        let added_total = total_milestone_amount(&milestone_amounts)?;
+        validate_due_dates(&milestone_due_dates, milestone_amounts.len())?;
+
+        emit!(MilestonesAdded {
+            escrow: ctx.accounts.escrow.key(),
+            job_hash: ctx.accounts.escrow.job_hash,
+            milestone_amounts: milestone_amounts.clone(),
+            added_amount: added_total,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        ctx.accounts
+            .escrow
+            .append_milestones(milestone_amounts, milestone_due_dates)?;
+        ctx.accounts.escrow.refresh_status();
+
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.recruiter.to_account_info(),
+                    to: ctx.accounts.vault.to_account_info(),
+                },
+            ),
+            added_total,
+        )
+    }
+
+    /// Recruiter reclaims the unapproved part of every milestone whose due
+    /// date passed without a submission. Approved amounts stay claimable.
+    pub fn reclaim_expired_milestones(ctx: Context<ReclaimExpiredMilestones>) -> Result<()> {
+        let escrow = &mut ctx.accounts.escrow;
+        escrow.require_active()?;
+
+        let now = Clock::get()?.unix_timestamp;
+        let refund = escrow.release_expired_milestones(now)?;
+        escrow.refresh_status();
+
+        emit!(ExpiredMilestonesReclaimed {
+            escrow: escrow.key(),
+            job_hash: escrow.job_hash,
+            amount: refund,
+            timestamp: now,
+        });
+
+        transfer_from_vault(
+            escrow,
+            &ctx.accounts.vault,
+            &ctx.accounts.recruiter.to_account_info(),
+            &ctx.accounts.system_program,
+            refund,
+        )
+    }
+
+    /// Closes a completed, cancelled or terminated escrow and its vault, and
+    /// returns the rent to the recruiter who paid for it. Permissionless.
+    pub fn close_completed_escrow(ctx: Context<CloseCompletedEscrow>) -> Result<()> {
+        let escrow = &ctx.accounts.escrow;
+        require!(
+            escrow.status != EscrowStatus::Disputed,
+            ErrorCode::EscrowDisputed
+        );
+        require!(escrow.status.is_terminal(), ErrorCode::EscrowNotSettled);
+        require!(escrow.collateral == 0, ErrorCode::CollateralNotSettled);
+
+        emit!(EscrowClosed {
+            escrow: escrow.key(),
+            job_hash: escrow.job_hash,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        // Draining the vault closes it
+        transfer_from_vault(
+            escrow,
+            &ctx.accounts.vault,
+            &ctx.accounts.recruiter.to_account_info(),
+            &ctx.accounts.system_program,
+            ctx.accounts.vault.lamports(),
+        )
+    }
+
+    /// Upgrades an escrow written in the version 0 layout (fixed three
+    /// milestones, bool approval flags) to the current layout in place, and
+    /// moves its unclaimed funds into a SOL vault. Permissionless; the payer
+    /// covers the extra rent. `approve_milestone`, `claim_milestone` and
+    /// `cancel_job` upgrade a legacy escrow themselves; any other instruction
+    /// needs this first.
+    pub fn migrate_escrow(ctx: Context<MigrateEscrow>) -> Result<()> {
+        let escrow_info = ctx.accounts.escrow.to_account_info();
+        let mut escrow = EscrowV0::try_from_account(&escrow_info)?.migrate();
+        upgrade_legacy_escrow(
+            &mut escrow,
+            &escrow_info,
+            &ctx.accounts.vault,
+            ctx.bumps.vault,
+            &ctx.accounts.payer.to_account_info(),
+            &ctx.accounts.system_program,
+        )?;
+        escrow.try_serialize(&mut &mut escrow_info.try_borrow_mut_data()?[..])?;
+
+        Ok(())
+    }
+
+    /// Creates a token-denominated escrow and locks the milestone total, plus
+    /// any advance, in a PDA-owned vault. Milestone amounts are in the mint's
+    /// base units. Token-2022 transfer fees are paid on top by the recruiter
+    /// so the vault receives the full amount.
+    pub fn create_token_job_escrow(
+        ctx: Context<CreateTokenJobEscrow>,
+        job_id: String,
+        freelancer: Pubkey,
+        milestone_amounts: Vec<u64>,
+        milestone_due_dates: Vec<i64>,
+        review_period: i64,
+        terms: JobTerms,
+    ) -> Result<()> {
+        require!(review_period > 0, ErrorCode::InvalidReviewPeriod);
+        let total_amount = total_milestone_amount(&milestone_amounts)?;
+        validate_due_dates(&milestone_due_dates, milestone_amounts.len())?;
+        terms.validate()?;
+        let locked_amount = total_amount
+            .checked_add(terms.advance)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+
+        let fee = inverse_transfer_fee(&ctx.accounts.mint.to_account_info(), locked_amount)?;
+        let deposit = locked_amount
+            .checked_add(fee)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+
+        // Transfer tokens from recruiter to the escrow vault
+        token_interface::transfer_checked(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.recruiter_token_account.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.vault.to_account_info(),
+                    authority: ctx.accounts.recruiter.to_account_info(),
+                },
+            ),
+            deposit,
+            ctx.accounts.mint.decimals,
+        )?;
+
+        ctx.accounts.vault.reload()?;
+        require!(
+            ctx.accounts.vault.amount >= locked_amount,
+            ErrorCode::InsufficientEscrowBalance
+        );
+
+        let escrow = &mut ctx.accounts.escrow;
+        escrow.version = ESCROW_VERSION;
+        escrow.recruiter = ctx.accounts.recruiter.key();
+        escrow.freelancer = freelancer;
+        escrow.job_hash = hash_job_id(&job_id);
+        escrow.set_milestones(milestone_amounts, milestone_due_dates);
+        escrow.review_period = review_period;
+        escrow.mint = Some(ctx.accounts.mint.key());
+        escrow.terms = terms;
+        escrow.fee_bps = ctx.accounts.config.fee_bps;
+        escrow.generation = Some(ctx.accounts.job_counter.next_generation()?);
+        escrow.collateral = 0;
+        escrow.status = EscrowStatus::Pending;
+        escrow.vault_bump = 0;
+        escrow.bump = ctx.bumps.escrow;
+        ctx.accounts.job_counter.bump = ctx.bumps.job_counter;
+
+        emit!(EscrowCreated {
+            escrow: escrow.key(),
+            job_id,
+            job_hash: escrow.job_hash,
+            recruiter: escrow.recruiter,
+            freelancer: escrow.freelancer,
+            mint: escrow.mint,
+            milestone_amounts: escrow.milestone_amounts.clone(),
+            total_amount,
+            terms: escrow.terms.clone(),
+            generation: escrow.generation,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Token-escrow variant of `accept_job`. The advance goes to the
+    /// freelancer's associated token account and the platform fee to the
+    /// treasury PDA's token account.
+    pub fn accept_token_job(
+        ctx: Context<AcceptTokenJob>,
+        terms_hash: [u8; 32],
+    ) -> Result<()> {
+        let escrow = &mut ctx.accounts.escrow;
+        let advance = escrow.accept(terms_hash, Clock::get()?.unix_timestamp)?;
+        let fee = platform_fee(advance, ctx.accounts.escrow.fee_bps)?;
+
+        if advance > 0 {
+            transfer_from_token_vault(
+                &ctx.accounts.escrow,
+                &ctx.accounts.vault,
+                &ctx.accounts.mint,
+                &ctx.accounts.freelancer_token_account,
+                &ctx.accounts.token_program,
+                advance - fee,
+            )?;
+        }
+        if fee > 0 {
+            transfer_from_token_vault(
+                &ctx.accounts.escrow,
+                &ctx.accounts.vault,
+                &ctx.accounts.mint,
+                &ctx.accounts.fee_treasury_token_account,
+                &ctx.accounts.token_program,
+                fee,
+            )?;
+        }
+
+        emit!(JobAccepted {
+            escrow: ctx.accounts.escrow.key(),
+            job_hash: ctx.accounts.escrow.job_hash,
+            freelancer: ctx.accounts.escrow.freelancer,
+            terms_hash,
+            advance,
+            fee,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Freelancer claims the approved part of a token escrow milestone into
+    /// their associated token account. The platform fee goes to the treasury
+    /// PDA's token account. Any Token-2022 transfer fee on the payout is
+    /// withheld from the amount received.
+    pub fn claim_token_milestone(
+        ctx: Context<ClaimTokenMilestone>,
+        milestone_index: u8,
+    ) -> Result<()> {
+        let escrow = &mut ctx.accounts.escrow;
+        escrow.require_active()?;
+        let index = escrow.milestone_index(milestone_index)?;
+
+        let amount = escrow.take_claimable(index)?;
+        escrow.refresh_status();
+        let fee = platform_fee(amount, escrow.fee_bps)?;
+
+        let timestamp = Clock::get()?.unix_timestamp;
+        emit!(MilestoneClaimed {
+            escrow: escrow.key(),
+            job_hash: escrow.job_hash,
+            milestone_index,
+            amount,
+            fee,
+            timestamp,
+        });
+
+        transfer_from_token_vault(
+            &ctx.accounts.escrow,
+            &ctx.accounts.vault,
+            &ctx.accounts.mint,
+            &ctx.accounts.freelancer_token_account,
+            &ctx.accounts.token_program,
+            amount - fee,
+        )?;
+
+        if fee > 0 {
+            transfer_from_token_vault(
+                &ctx.accounts.escrow,
+                &ctx.accounts.vault,
+                &ctx.accounts.mint,
+                &ctx.accounts.fee_treasury_token_account,
+                &ctx.accounts.token_program,
+                fee,
+            )?;
+
+            emit!(FeeCollected {
+                escrow: ctx.accounts.escrow.key(),
+                job_hash: ctx.accounts.escrow.job_hash,
+                mint: ctx.accounts.escrow.mint,
+                milestone_index,
+                amount: fee,
+                timestamp,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Token-escrow variant of `cancel_job`, with the same consent rules.
+    /// The empty vault is closed by `close_completed_token_escrow`.
+    pub fn cancel_token_job(ctx: Context<CancelTokenJob>) -> Result<()> {
+        if ctx.accounts.escrow.status != EscrowStatus::Pending {
+            ctx.accounts.escrow.require_active()?;
+            require!(
+                ctx.accounts.freelancer.is_some(),
+                ErrorCode::FreelancerConsentRequired
+            );
+        }
+        ctx.accounts.escrow.require_cancellable()?;
+        ctx.accounts.escrow.status = EscrowStatus::Cancelled;
+
+        let escrow = &ctx.accounts.escrow;
+
+        // Refund everything in the vault but the freelancer's collateral,
+        // including any deposit rounding dust
+        let refund_amount = ctx
+            .accounts
+            .vault
+            .amount
+            .checked_sub(escrow.collateral)
+            .ok_or(ErrorCode::InsufficientEscrowBalance)?;
+        transfer_from_token_vault(
+            escrow,
+            &ctx.accounts.vault,
+            &ctx.accounts.mint,
+            &ctx.accounts.recruiter_token_account,
+            &ctx.accounts.token_program,
+            refund_amount,
+        )?;
+
+        emit!(JobCancelled {
+            escrow: escrow.key(),
+            job_hash: escrow.job_hash,
+            refund_amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Token-escrow variant of `refund_unaccepted_job`. Permissionless.
+    pub fn refund_unaccepted_token_job(
+        ctx: Context<RefundUnacceptedTokenJob>,
+    ) -> Result<()> {
+        ctx.accounts
+            .escrow
+            .require_acceptance_expired(Clock::get()?.unix_timestamp)?;
+        ctx.accounts.escrow.status = EscrowStatus::Cancelled;
+
+        let escrow = &ctx.accounts.escrow;
+
+        // Refund everything in the vault, including any deposit rounding dust
+        let refund_amount = ctx.accounts.vault.amount;
+        transfer_from_token_vault(
+            escrow,
+            &ctx.accounts.vault,
+            &ctx.accounts.mint,
+            &ctx.accounts.recruiter_token_account,
+            &ctx.accounts.token_program,
+            refund_amount,
+        )?;
+
+        emit!(JobCancelled {
+            escrow: escrow.key(),
+            job_hash: escrow.job_hash,
+            refund_amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Creates the singleton platform config. Only the program's upgrade
+    /// authority may initialize it, so nobody can front-run the deployment.
+    pub fn initialize_config(
+        ctx: Context<InitializeConfig>,
+        authority: Pubkey,
+        treasury: Pubkey,
+        fee_bps: u16,
+        arbitrator: Pubkey,
+        dispute_bond: u64,
+        application_bond: u64,
+    ) -> Result<()> {
+        require!(fee_bps <= MAX_FEE_BPS, ErrorCode::InvalidFeeBps);
+
+        let config = &mut ctx.accounts.config;
+        config.authority = authority;
+        config.pending_authority = None;
+        config.treasury = treasury;
+        config.fee_bps = fee_bps;
+        config.arbitrator = arbitrator;
+        config.dispute_bond = dispute_bond;
+        config.application_bond = application_bond;
+        config.paused = PauseFlags::default();
+        config.bond_mints = Vec::new();
+        config.bump = ctx.bumps.config;
+
+        let fee_treasury = &mut ctx.accounts.fee_treasury;
+        fee_treasury.accrued_lamports = 0;
+        fee_treasury.total_lamports_collected = 0;
+        fee_treasury.bump = ctx.bumps.fee_treasury;
+
+        emit!(ConfigUpdated {
+            authority,
+            treasury,
+            fee_bps,
+            arbitrator,
+            dispute_bond,
+            application_bond,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Platform authority updates the config. The authority itself is
+    /// rotated with `propose_authority_transfer` / `accept_authority_transfer`,
+    /// and the fee and treasury through a multisig `SetFees` proposal.
+    pub fn update_config(
+        ctx: Context<UpdateConfig>,
+        arbitrator: Pubkey,
+        dispute_bond: u64,
+        application_bond: u64,
+    ) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        config.arbitrator = arbitrator;
+        config.dispute_bond = dispute_bond;
+        config.application_bond = application_bond;
+
+        emit!(ConfigUpdated {
+            authority: config.authority,
+            treasury: config.treasury,
+            fee_bps: config.fee_bps,
+            arbitrator,
+            dispute_bond,
+            application_bond,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Platform authority pauses or resumes instruction classes (circuit
+    /// breaker). Dispute resolution is never paused.
+    pub fn set_pause_flags(ctx: Context<UpdateConfig>, paused: PauseFlags) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        config.paused = paused.clone();
+
+        emit!(PauseFlagsUpdated {
+            authority: config.authority,
+            paused,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Platform authority accepts `mint` for token application bonds of at
+    /// least `min_bond`, or updates its minimum. A `min_bond` of 0 removes the
+    /// mint; applications already bonded in it are unaffected.
+    pub fn set_bond_mint(
+        ctx: Context<UpdateConfig>,
+        mint: Pubkey,
+        min_bond: u64,
+    ) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let existing = config
+            .bond_mints
+            .iter()
+            .position(|bond_mint| bond_mint.mint == mint);
+        match (existing, min_bond) {
+            (Some(index), 0) => {
+                config.bond_mints.remove(index);
+            }
+            (Some(index), _) => config.bond_mints[index].min_bond = min_bond,
+            (None, 0) => {}
+            (None, _) => {
+                require!(
+                    config.bond_mints.len() < MAX_BOND_MINTS,
+                    ErrorCode::TooManyBondMints
+                );
+                config.bond_mints.push(BondMint { mint, min_bond });
+            }
+        }
+
+        emit!(BondMintUpdated {
+            authority: config.authority,
+            mint,
+            min_bond,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Platform authority nominates a new authority. Nothing changes until the
+    /// nominee accepts; a new proposal replaces any pending one.
+    pub fn propose_authority_transfer(
+        ctx: Context<ProposeAuthorityTransfer>,
+        new_authority: Pubkey,
+    ) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        config.pending_authority = Some(new_authority);
+
+        emit!(AuthorityTransferProposed {
+            authority: config.authority,
+            pending_authority: new_authority,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// The nominated key signs to take over as platform authority
+    pub fn accept_authority_transfer(ctx: Context<AcceptAuthorityTransfer>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let previous_authority = config.authority;
+        config.authority = ctx.accounts.new_authority.key();
+        config.pending_authority = None;
+
+        emit!(AuthorityTransferred {
+            previous_authority,
+            new_authority: config.authority,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Recruiter or freelancer opens a dispute, posting the configured bond.
+    /// Approvals, claims and cancellation are frozen until it is resolved.
+    pub fn open_dispute(ctx: Context<OpenDispute>, reason_hash: [u8; 32]) -> Result<()> {
+        ctx.accounts.escrow.require_active()?;
+
+        let bond = ctx.accounts.config.dispute_bond;
+        if bond > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.disputant.to_account_info(),
+                        to: ctx.accounts.dispute.to_account_info(),
+                    },
+                ),
+                bond,
+            )?;
+        }
+
+        let dispute = &mut ctx.accounts.dispute;
+        dispute.escrow = ctx.accounts.escrow.key();
+        dispute.opened_by = ctx.accounts.disputant.key();
+        dispute.arbitrator = ctx.accounts.config.arbitrator;
+        dispute.bond = bond;
+        dispute.reason_hash = reason_hash;
+        dispute.opened_at = Clock::get()?.unix_timestamp;
+        dispute.bump = ctx.bumps.dispute;
+
+        ctx.accounts.escrow.status = EscrowStatus::Disputed;
+
+        emit!(DisputeOpened {
+            escrow: ctx.accounts.escrow.key(),
+            job_hash: ctx.accounts.escrow.job_hash,
+            opened_by: dispute.opened_by,
+            arbitrator: dispute.arbitrator,
+            bond,
+            reason_hash,
+            timestamp: dispute.opened_at,
+        });
+
+        Ok(())
+    }
+
+    /// Arbitrator settles a SOL escrow. `freelancer_amounts[i]` is the part of
+    /// milestone `i` awarded to the freelancer; the rest is refunded to the
+    /// recruiter. The bond is refunded to the opener or forfeited to the treasury.
+    /// The escrow is left Completed, ready for `close_completed_escrow`.
+    pub fn resolve_dispute(
+        ctx: Context<ResolveDispute>,
+        freelancer_amounts: Vec<u64>,
+        refund_bond: bool,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.escrow.status == EscrowStatus::Disputed,
+            ErrorCode::EscrowNotDisputed
+        );
+        let award = freelancer_dispute_award(&ctx.accounts.escrow, &freelancer_amounts)?;
+        let outstanding = ctx.accounts.escrow.outstanding_amount()?;
+
+        emit!(DisputeResolved {
+            escrow: ctx.accounts.escrow.key(),
+            job_hash: ctx.accounts.escrow.job_hash,
+            arbitrator: ctx.accounts.arbitrator.key(),
+            freelancer_amounts: freelancer_amounts.clone(),
+            freelancer_amount: award,
+            recruiter_amount: outstanding - award,
+            bond_refunded: refund_bond,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        ctx.accounts.escrow.apply_dispute_ruling(&freelancer_amounts);
+
+        transfer_from_vault(
+            &ctx.accounts.escrow,
+            &ctx.accounts.vault,
+            &ctx.accounts.freelancer.to_account_info(),
+            &ctx.accounts.system_program,
+            award,
+        )?;
+        transfer_from_vault(
+            &ctx.accounts.escrow,
+            &ctx.accounts.vault,
+            &ctx.accounts.recruiter.to_account_info(),
+            &ctx.accounts.system_program,
+            outstanding - award,
+        )?;
+
+        settle_dispute_bond(
+            &ctx.accounts.dispute,
+            &ctx.accounts.treasury.to_account_info(),
+            refund_bond,
+        )
+    }
+
+    /// Token-escrow variant of `resolve_dispute`
+    pub fn resolve_token_dispute(
+        ctx: Context<ResolveTokenDispute>,
+        freelancer_amounts: Vec<u64>,
+        refund_bond: bool,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.escrow.status == EscrowStatus::Disputed,
+            ErrorCode::EscrowNotDisputed
+        );
+        let award = freelancer_dispute_award(&ctx.accounts.escrow, &freelancer_amounts)?;
+        let outstanding = ctx.accounts.escrow.outstanding_amount()?;
+
+        emit!(DisputeResolved {
+            escrow: ctx.accounts.escrow.key(),
+            job_hash: ctx.accounts.escrow.job_hash,
+            arbitrator: ctx.accounts.arbitrator.key(),
+            freelancer_amounts: freelancer_amounts.clone(),
+            freelancer_amount: award,
+            recruiter_amount: outstanding - award,
+            bond_refunded: refund_bond,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        ctx.accounts.escrow.apply_dispute_ruling(&freelancer_amounts);
+
+        if award > 0 {
+            transfer_from_token_vault(
+                &ctx.accounts.escrow,
+                &ctx.accounts.vault,
+                &ctx.accounts.mint,
+                &ctx.accounts.freelancer_token_account,
+                &ctx.accounts.token_program,
+                award,
+            )?;
+        }
+
+        // Everything left in the vault but the collateral (recruiter's share
+        // plus any dust) is refunded
+        ctx.accounts.vault.reload()?;
+        let remaining = ctx
+            .accounts
+            .vault
+            .amount
+            .checked_sub(ctx.accounts.escrow.collateral)
+            .ok_or(ErrorCode::InsufficientEscrowBalance)?;
+        if remaining > 0 {
+            transfer_from_token_vault(
+                &ctx.accounts.escrow,
+                &ctx.accounts.vault,
+                &ctx.accounts.mint,
+                &ctx.accounts.recruiter_token_account,
+                &ctx.accounts.token_program,
+                remaining,
+            )?;
+        }
+
+        settle_dispute_bond(
+            &ctx.accounts.dispute,
+            &ctx.accounts.treasury.to_account_info(),
+            refund_bond,
+        )
+    }
+
+    /// Token-escrow variant of `close_completed_escrow`. Any rounding dust
+    /// left in the vault goes back to the recruiter, and withheld Token-2022
+    /// transfer fees are harvested to the mint, before it is closed.
+    pub fn close_completed_token_escrow(
+        ctx: Context<CloseCompletedTokenEscrow>,
+    ) -> Result<()> {
+        let escrow = &ctx.accounts.escrow;
+        require!(
+            escrow.status != EscrowStatus::Disputed,
+            ErrorCode::EscrowDisputed
+        );
+        require!(escrow.status.is_terminal(), ErrorCode::EscrowNotSettled);
+        require!(escrow.collateral == 0, ErrorCode::CollateralNotSettled);
+
+        emit!(EscrowClosed {
+            escrow: escrow.key(),
+            job_hash: escrow.job_hash,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        let dust = ctx.accounts.vault.amount;
+        if dust > 0 {
+            transfer_from_token_vault(
+                escrow,
+                &ctx.accounts.vault,
+                &ctx.accounts.mint,
+                &ctx.accounts.recruiter_token_account,
+                &ctx.accounts.token_program,
+                dust,
+            )?;
+        }
+        close_token_vault(
+            escrow,
+            &ctx.accounts.vault,
+            &ctx.accounts.mint,
+            &ctx.accounts.recruiter.to_account_info(),
+            &ctx.accounts.token_program,
+        )
+    }
+
+    /// Token-escrow variant of `amend_escrow`
+    pub fn amend_token_escrow(
+        ctx: Context<AmendTokenEscrow>,
+        milestone_amounts: Vec<u64>,
+        milestone_due_dates: Vec<i64>,
+    ) -> Result<()> {
+        ctx.accounts.escrow.require_active()?;
+
+        let new_total = ctx.accounts.escrow.amended_total(&milestone_amounts)?;
+        let old_total = ctx.accounts.escrow.total_amount()?;
+        ctx.accounts
+            .escrow
+            .amend_milestones(milestone_amounts, milestone_due_dates)?;
+        ctx.accounts.escrow.refresh_status();
+
+        emit!(EscrowAmended {
+            escrow: ctx.accounts.escrow.key(),
+            job_hash: ctx.accounts.escrow.job_hash,
+            milestone_amounts: ctx.accounts.escrow.milestone_amounts.clone(),
+            previous_total: old_total,
+            new_total,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        if new_total > old_total {
+            let increase = new_total - old_total;
+            let fee = inverse_transfer_fee(&ctx.accounts.mint.to_account_info(), increase)?;
+            let deposit = increase
+                .checked_add(fee)
+                .ok_or(ErrorCode::ArithmeticOverflow)?;
+
+            token_interface::transfer_checked(
+                CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    TransferChecked {
+                        from: ctx.accounts.recruiter_token_account.to_account_info(),
+                        mint: ctx.accounts.mint.to_account_info(),
+                        to: ctx.accounts.vault.to_account_info(),
+                        authority: ctx.accounts.recruiter.to_account_info(),
+                    },
+                ),
+                deposit,
+                ctx.accounts.mint.decimals,
+            )?;
+        } else if new_total < old_total {
+            transfer_from_token_vault(
+                &ctx.accounts.escrow,
+                &ctx.accounts.vault,
+                &ctx.accounts.mint,
+                &ctx.accounts.recruiter_token_account,
+                &ctx.accounts.token_program,
+                old_total - new_total,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Token-escrow variant of `add_milestones`
+    pub fn add_token_milestones(
+        ctx: Context<AddTokenMilestones>,
+        milestone_amounts: Vec<u64>,
+        milestone_due_dates: Vec<i64>,
+    ) -> Result<()> {
+        ctx.accounts.escrow.require_active()?;
+
+        let added_total = total_milestone_amount(&milestone_amounts)?;
+        validate_due_dates(&milestone_due_dates, milestone_amounts.len())?;
+
+        emit!(MilestonesAdded {
+            escrow: ctx.accounts.escrow.key(),
+            job_hash: ctx.accounts.escrow.job_hash,
+            milestone_amounts: milestone_amounts.clone(),
+            added_amount: added_total,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        ctx.accounts
+            .escrow
+            .append_milestones(milestone_amounts, milestone_due_dates)?;
+        ctx.accounts.escrow.refresh_status();
+
+        let fee = inverse_transfer_fee(&ctx.accounts.mint.to_account_info(), added_total)?;
+        let deposit = added_total
+            .checked_add(fee)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+
+        token_interface::transfer_checked(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.recruiter_token_account.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.vault.to_account_info(),
+                    authority: ctx.accounts.recruiter.to_account_info(),
+                },
+            ),
+            deposit,
+            ctx.accounts.mint.decimals,
+        )
+    }
+
+    /// Token-escrow variant of `refund_milestone_remainder`
+    pub fn refund_token_milestone_remainder(
+        ctx: Context<RefundTokenMilestoneRemainder>,
+        milestone_index: u8,
+    ) -> Result<()> {
+        let escrow = &mut ctx.accounts.escrow;
+        escrow.require_active()?;
+        let index = escrow.milestone_index(milestone_index)?;
+
+        let refund = escrow.release_unapproved_remainder(index)?;
+        escrow.refresh_status();
+
+        emit!(MilestoneRemainderRefunded {
+            escrow: escrow.key(),
+            job_hash: escrow.job_hash,
+            milestone_index,
+            amount: refund,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        transfer_from_token_vault(
+            &ctx.accounts.escrow,
+            &ctx.accounts.vault,
+            &ctx.accounts.mint,
+            &ctx.accounts.recruiter_token_account,
+            &ctx.accounts.token_program,
+            refund,
+        )
+    }
+
+    /// Token-escrow variant of `reclaim_expired_milestones`
+    pub fn reclaim_expired_token_milestones(
+        ctx: Context<ReclaimExpiredTokenMilestones>,
+    ) -> Result<()> {
+        let escrow = &mut ctx.accounts.escrow;
+        escrow.require_active()?;
+
+        let now = Clock::get()?.unix_timestamp;
+        let refund = escrow.release_expired_milestones(now)?;
+        escrow.refresh_status();
+
+        emit!(ExpiredMilestonesReclaimed {
+            escrow: escrow.key(),
+            job_hash: escrow.job_hash,
+            amount: refund,
+            timestamp: now,
+        });
+
+        transfer_from_token_vault(
+            &ctx.accounts.escrow,
+            &ctx.accounts.vault,
+            &ctx.accounts.mint,
+            &ctx.accounts.recruiter_token_account,
+            &ctx.accounts.token_program,
+            refund,
+        )
+    }
+
+    /// Platform authority sets up the multisig that gates platform powers
+    /// (withdrawals, emergency closes). Afterwards the owner set can only be
+    /// changed by a `SetOwners` proposal. Approved withdrawals and emergency
+    /// closes wait `timelock_delay` seconds before they can be executed.
+    pub fn initialize_multisig(
+        ctx: Context<InitializeMultisig>,
+        owners: Vec<Pubkey>,
+        threshold: u8,
+        timelock_delay: i64,
+    ) -> Result<()> {
+        validate_multisig_owners(&owners, threshold)?;
+        require!(timelock_delay >= 0, ErrorCode::InvalidTimelockDelay);
+
+        let multisig = &mut ctx.accounts.multisig;
+        multisig.owners = owners;
+        multisig.threshold = threshold;
+        multisig.timelock_delay = timelock_delay;
+        multisig.owner_set_seqno = 0;
+        multisig.proposal_count = 0;
+        multisig.bump = ctx.bumps.multisig;
+
+        emit!(MultisigOwnersChanged {
+            owners: multisig.owners.clone(),
+            threshold,
+            owner_set_seqno: 0,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// A multisig owner proposes a platform action. The proposer's own
+    /// approval is recorded immediately. Actions against an escrow take the
+    /// escrow account: closing or slashing requires it to already be frozen,
+    /// so the notice period runs while neither party can move funds.
+    pub fn propose_platform_action(
+        ctx: Context<ProposePlatformAction>,
+        action: PlatformAction,
+    ) -> Result<()> {
+        let owner_index = ctx
+            .accounts
+            .multisig
+            .owner_index(&ctx.accounts.proposer.key())?;
+
+        match &action {
+            PlatformAction::EmergencyClose { escrow }
+            | PlatformAction::SlashCollateral { escrow } => {
+                let target = ctx
+                    .accounts
+                    .escrow
+                    .as_ref()
+                    .ok_or(ErrorCode::ProposalActionMismatch)?;
+                require_keys_eq!(*escrow, target.key(), ErrorCode::ProposalActionMismatch);
+                require!(
+                    target.status.allows_platform_action(),
+                    ErrorCode::PlatformActionNotAllowed
+                );
+            }
+            PlatformAction::FlagEscrow { escrow } => {
+                let target = ctx
+                    .accounts
+                    .escrow
+                    .as_ref()
+                    .ok_or(ErrorCode::ProposalActionMismatch)?;
+                require_keys_eq!(*escrow, target.key(), ErrorCode::ProposalActionMismatch);
+                target.require_active()?;
+            }
+            PlatformAction::SetOwners { owners, threshold } => {
+                validate_multisig_owners(owners, *threshold)?;
+            }
+            PlatformAction::SetFees { fee_bps, .. } => {
+                require!(*fee_bps <= MAX_FEE_BPS, ErrorCode::InvalidFeeBps);
+            }
+            PlatformAction::WithdrawFees { .. } => {}
+        }
+
+        let multisig = &mut ctx.accounts.multisig;
+        let index = multisig.proposal_count;
+        multisig.proposal_count = index
+            .checked_add(1)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+
+        let mut approvals = vec![false; multisig.owners.len()];
+        approvals[owner_index] = true;
+
+        let proposal = &mut ctx.accounts.proposal;
+        proposal.multisig = multisig.key();
+        proposal.index = index;
+        proposal.proposer = ctx.accounts.proposer.key();
+        proposal.action = action;
+        proposal.approvals = approvals;
+        proposal.owner_set_seqno = multisig.owner_set_seqno;
+        proposal.created_at = Clock::get()?.unix_timestamp;
+        proposal.executable_at = 0;
+        proposal.bump = ctx.bumps.proposal;
+
+        emit!(ProposalCreated {
+            proposal: proposal.key(),
+            index,
+            proposer: proposal.proposer,
+            action: proposal.action.clone(),
+            timestamp: proposal.created_at,
+        });
+
+        // A 1-of-N multisig queues the action straight away
+        let proposal_key = proposal.key();
+        proposal.queue_if_approved(proposal_key, multisig)
+    }
+
+    /// A multisig owner approves a pending proposal
+    pub fn approve_proposal(ctx: Context<ApproveProposal>) -> Result<()> {
+        let multisig = &ctx.accounts.multisig;
+        let proposal = &mut ctx.accounts.proposal;
+        require!(
+            proposal.owner_set_seqno == multisig.owner_set_seqno,
+            ErrorCode::StaleProposal
+        );
+
+        let owner_index = multisig.owner_index(&ctx.accounts.owner.key())?;
+        require!(
+            !proposal.approvals[owner_index],
+            ErrorCode::ProposalAlreadyApproved
+        );
+        proposal.approvals[owner_index] = true;
+
+        emit!(ProposalApproved {
+            proposal: proposal.key(),
+            owner: ctx.accounts.owner.key(),
+            approvals: proposal.approval_count(),
+            threshold: multisig.threshold,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        let proposal_key = proposal.key();
+        proposal.queue_if_approved(proposal_key, multisig)
+    }
+
+    /// Any multisig owner can cancel a pending or queued proposal, e.g. to
+    /// veto a withdrawal during its notice period. Rent goes to the proposer.
+    pub fn cancel_proposal(ctx: Context<CancelProposal>) -> Result<()> {
+        ctx.accounts
+            .multisig
+            .owner_index(&ctx.accounts.owner.key())?;
+
+        emit!(ProposalCancelled {
+            proposal: ctx.accounts.proposal.key(),
+            cancelled_by: ctx.accounts.owner.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Executes an approved `SetOwners` proposal. Proposals made under the
+    /// previous owner set become stale and can no longer be executed.
+    pub fn set_multisig_owners(ctx: Context<SetMultisigOwners>) -> Result<()> {
+        ctx.accounts
+            .multisig
+            .require_approved(&ctx.accounts.proposal)?;
+        let PlatformAction::SetOwners { owners, threshold } = ctx.accounts.proposal.action.clone()
+        else {
+            return err!(ErrorCode::ProposalActionMismatch);
+        };
+
+        let multisig = &mut ctx.accounts.multisig;
+        multisig.owners = owners;
+        multisig.threshold = threshold;
+        multisig.owner_set_seqno = multisig
+            .owner_set_seqno
+            .checked_add(1)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+
+        emit!(MultisigOwnersChanged {
+            owners: multisig.owners.clone(),
+            threshold,
+            owner_set_seqno: multisig.owner_set_seqno,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Executes an approved `SetFees` proposal. Escrows keep the fee they
+    /// were created with, so only new escrows see the change.
+    pub fn set_fees(ctx: Context<SetFees>) -> Result<()> {
+        ctx.accounts
+            .multisig
+            .require_approved(&ctx.accounts.proposal)?;
+        let PlatformAction::SetFees { treasury, fee_bps } = ctx.accounts.proposal.action else {
+            return err!(ErrorCode::ProposalActionMismatch);
+        };
+
+        let config = &mut ctx.accounts.config;
+        config.treasury = treasury;
+        config.fee_bps = fee_bps;
+
+        emit!(ConfigUpdated {
+            authority: config.authority,
+            treasury,
+            fee_bps,
+            arbitrator: config.arbitrator,
+            dispute_bond: config.dispute_bond,
+            application_bond: config.application_bond,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Executes an approved `WithdrawFees` proposal for SOL, moving accrued
+    /// fees from the treasury PDA to the configured treasury wallet. Only
+    /// fees on the ledger can be moved.
+    pub fn withdraw_fees(ctx: Context<WithdrawFees>) -> Result<()> {
+        ctx.accounts
+            .multisig
+            .require_approved(&ctx.accounts.proposal)?;
+        let PlatformAction::WithdrawFees { mint: None, amount } = ctx.accounts.proposal.action
+        else {
+            return err!(ErrorCode::ProposalActionMismatch);
+        };
+
+        let fee_treasury = &mut ctx.accounts.fee_treasury;
+        fee_treasury.accrued_lamports = fee_treasury
+            .accrued_lamports
+            .checked_sub(amount)
+            .ok_or(ErrorCode::InsufficientAccruedFees)?;
+
+        transfer_lamports(
+            &fee_treasury.to_account_info(),
+            &ctx.accounts.treasury.to_account_info(),
+            amount,
+        )?;
+
+        emit!(FeesWithdrawn {
+            mint: None,
+            recipient: ctx.accounts.treasury.key(),
+            amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Token variant of `withdraw_fees`. The treasury PDA's token account for
+    /// the mint holds nothing but fees, so its balance is the ledger.
+    pub fn withdraw_token_fees(ctx: Context<WithdrawTokenFees>) -> Result<()> {
+        ctx.accounts
+            .multisig
+            .require_approved(&ctx.accounts.proposal)?;
+        let PlatformAction::WithdrawFees { mint: Some(mint), amount } =
+            ctx.accounts.proposal.action
+        else {
+            return err!(ErrorCode::ProposalActionMismatch);
+        };
+        require_keys_eq!(
+            mint,
+            ctx.accounts.mint.key(),
+            ErrorCode::ProposalActionMismatch
+        );
+
+        require!(
+            amount <= ctx.accounts.fee_treasury_token_account.amount,
+            ErrorCode::InsufficientAccruedFees
+        );
+
+        let signer_seeds: &[&[&[u8]]] = &[&[b"treasury", &[ctx.accounts.fee_treasury.bump]]];
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.fee_treasury_token_account.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.treasury_token_account.to_account_info(),
+                    authority: ctx.accounts.fee_treasury.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            amount,
+            ctx.accounts.mint.decimals,
+        )?;
+
+        emit!(FeesWithdrawn {
+            mint: Some(ctx.accounts.mint.key()),
+            recipient: ctx.accounts.treasury_token_account.key(),
+            amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Executes an approved `FlagEscrow` proposal. A flagged escrow is frozen
+    /// for both parties and becomes eligible for platform action.
+    pub fn flag_escrow(ctx: Context<FlagEscrow>) -> Result<()> {
+        ctx.accounts
+            .multisig
+            .require_approved(&ctx.accounts.proposal)?;
+        let PlatformAction::FlagEscrow { escrow } = ctx.accounts.proposal.action else {
+            return err!(ErrorCode::ProposalActionMismatch);
+        };
+        require_keys_eq!(
+            escrow,
+            ctx.accounts.escrow.key(),
+            ErrorCode::ProposalActionMismatch
+        );
+
+        let escrow = &mut ctx.accounts.escrow;
+        escrow.require_active()?;
+        escrow.status = EscrowStatus::Flagged;
+
+        emit!(EscrowFlagged {
+            escrow: escrow.key(),
+            job_hash: escrow.job_hash,
+            flagged: true,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Platform authority clears a flag, returning the escrow to Funded or
+    /// InProgress depending on its milestones. Unfreezing needs only the
+    /// authority so a wrongly flagged escrow is not stuck behind a vote.
+    pub fn unflag_escrow(ctx: Context<UnflagEscrow>) -> Result<()> {
+        let escrow = &mut ctx.accounts.escrow;
+        require!(
+            escrow.status == EscrowStatus::Flagged,
+            ErrorCode::EscrowNotFlagged
+        );
+        escrow.status = EscrowStatus::Funded;
+        escrow.refresh_status();
+
+        emit!(EscrowFlagged {
+            escrow: escrow.key(),
+            job_hash: escrow.job_hash,
+            flagged: false,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Platform terminates a disputed or flagged escrow once an
+    /// `EmergencyClose` proposal has reached the multisig threshold.
+    /// Approved-but-unclaimed funds go to the freelancer (less the platform
+    /// fee) and the unapproved rest goes back to the recruiter. A disputed
+    /// escrow's dispute is closed, refunding the bond to its opener. Collateral
+    /// stays in the vault, so slash it first if the freelancer is at fault.
+    /// The account remains as a Terminated record until `close_completed_escrow`.
+    pub fn platform_emergency_close(
+        ctx: Context<PlatformEmergencyClose>,
+    ) -> Result<()> {
+        ctx.accounts
+            .multisig
+            .require_approved(&ctx.accounts.proposal)?;
+        let PlatformAction::EmergencyClose { escrow } = ctx.accounts.proposal.action else {
+            return err!(ErrorCode::ProposalActionMismatch);
+        };
+        require_keys_eq!(
+            escrow,
+            ctx.accounts.escrow.key(),
+            ErrorCode::ProposalActionMismatch
+        );
+
+        let escrow = &mut ctx.accounts.escrow;
+        require!(
+            escrow.status.allows_platform_action(),
+            ErrorCode::PlatformActionNotAllowed
+        );
+        if escrow.status == EscrowStatus::Disputed {
+            require!(
+                ctx.accounts.dispute.is_some(),
+                ErrorCode::DisputeAccountRequired
+            );
+        }
+        let outstanding = escrow.outstanding_amount()?;
+        let approved_amount = escrow.settle_approved()?;
+        escrow.status = EscrowStatus::Terminated;
+
+        let fee = platform_fee(approved_amount, escrow.fee_bps)?;
+        let freelancer_amount = approved_amount - fee;
+        let recruiter_amount = outstanding - approved_amount;
+
+        transfer_from_vault(
+            &ctx.accounts.escrow,
+            &ctx.accounts.vault,
+            &ctx.accounts.freelancer.to_account_info(),
+            &ctx.accounts.system_program,
+            freelancer_amount,
+        )?;
+        if fee > 0 {
+            transfer_from_vault(
+                &ctx.accounts.escrow,
+                &ctx.accounts.vault,
+                &ctx.accounts.fee_treasury.to_account_info(),
+                &ctx.accounts.system_program,
+                fee,
+            )?;
+            ctx.accounts.fee_treasury.credit_lamports(fee)?;
+        }
+        transfer_from_vault(
+            &ctx.accounts.escrow,
+            &ctx.accounts.vault,
+            &ctx.accounts.recruiter.to_account_info(),
+            &ctx.accounts.system_program,
+            recruiter_amount,
+        )?;
+
+        emit!(EmergencyClose {
+            escrow: ctx.accounts.escrow.key(),
+            job_hash: ctx.accounts.escrow.job_hash,
+            proposal: ctx.accounts.proposal.key(),
+            freelancer_amount,
+            recruiter_amount,
+            fee,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Token-escrow variant of `platform_emergency_close`. Everything in the
+    /// vault but the collateral and the freelancer's share goes back to the
+    /// recruiter, including deposit rounding dust. With no collateral left,
+    /// the vault's withheld transfer fees are harvested and the vault and
+    /// escrow are closed to the recruiter; otherwise `close_completed_token_escrow`
+    /// does that once the collateral is released or slashed.
+    pub fn platform_emergency_close_token(
+        ctx: Context<PlatformEmergencyCloseToken>,
+    ) -> Result<()> {
+        ctx.accounts
+            .multisig
+            .require_approved(&ctx.accounts.proposal)?;
+        let PlatformAction::EmergencyClose { escrow } = ctx.accounts.proposal.action else {
+            return err!(ErrorCode::ProposalActionMismatch);
+        };
+        require_keys_eq!(
+            escrow,
+            ctx.accounts.escrow.key(),
+            ErrorCode::ProposalActionMismatch
+        );
+
+        let escrow = &mut ctx.accounts.escrow;
+        require!(
+            escrow.status.allows_platform_action(),
+            ErrorCode::PlatformActionNotAllowed
+        );
+        if escrow.status == EscrowStatus::Disputed {
+            require!(
+                ctx.accounts.dispute.is_some(),
+                ErrorCode::DisputeAccountRequired
+            );
+        }
+        let approved_amount = escrow.settle_approved()?;
+        escrow.status = EscrowStatus::Terminated;
+
+        let fee = platform_fee(approved_amount, escrow.fee_bps)?;
+        let freelancer_amount = approved_amount - fee;
+
+        if freelancer_amount > 0 {
+            transfer_from_token_vault(
+                &ctx.accounts.escrow,
+                &ctx.accounts.vault,
+                &ctx.accounts.mint,
+                &ctx.accounts.freelancer_token_account,
+                &ctx.accounts.token_program,
+                freelancer_amount,
+            )?;
+        }
+        if fee > 0 {
+            transfer_from_token_vault(
+                &ctx.accounts.escrow,
+                &ctx.accounts.vault,
+                &ctx.accounts.mint,
+                &ctx.accounts.fee_treasury_token_account,
+                &ctx.accounts.token_program,
+                fee,
+            )?;
+        }
+
+        ctx.accounts.vault.reload()?;
+        let recruiter_amount = ctx
+            .accounts
+            .vault
+            .amount
+            .checked_sub(ctx.accounts.escrow.collateral)
+            .ok_or(ErrorCode::InsufficientEscrowBalance)?;
+        if recruiter_amount > 0 {
+            transfer_from_token_vault(
+                &ctx.accounts.escrow,
+                &ctx.accounts.vault,
+                &ctx.accounts.mint,
+                &ctx.accounts.recruiter_token_account,
+                &ctx.accounts.token_program,
+                recruiter_amount,
+            )?;
+        }
+
+        let timestamp = Clock::get()?.unix_timestamp;
+        emit!(EmergencyClose {
+            escrow: ctx.accounts.escrow.key(),
+            job_hash: ctx.accounts.escrow.job_hash,
+            proposal: ctx.accounts.proposal.key(),
+            freelancer_amount,
+            recruiter_amount,
+            fee,
+            timestamp,
+        });
+
+        if ctx.accounts.escrow.collateral > 0 {
+            return Ok(());
+        }
+
+        emit!(EscrowClosed {
+            escrow: ctx.accounts.escrow.key(),
+            job_hash: ctx.accounts.escrow.job_hash,
+            timestamp,
+        });
+
+        close_token_vault(
+            &ctx.accounts.escrow,
+            &ctx.accounts.vault,
+            &ctx.accounts.mint,
+            &ctx.accounts.recruiter.to_account_info(),
+            &ctx.accounts.token_program,
+        )?;
+        ctx.accounts
+            .escrow
+            .close(ctx.accounts.recruiter.to_account_info())
+    }
+
+    /// Freelancer applies to a job by posting a SOL bond of at least the
+    /// configured minimum into the application PDA
+    pub fn apply_to_job(
+        ctx: Context<ApplyToJob>,
+        job_id: String,
+        recruiter: Pubkey,
+        bond: u64,
+    ) -> Result<()> {
+        require!(
+            bond > 0 && bond >= ctx.accounts.config.application_bond,
+            ErrorCode::InsufficientApplicationBond
+        );
+
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.freelancer.to_account_info(),
+                    to: ctx.accounts.application.to_account_info(),
+                },
+            ),
+            bond,
+        )?;
+
+        let application = &mut ctx.accounts.application;
+        application.recruiter = recruiter;
+        application.freelancer = ctx.accounts.freelancer.key();
+        application.job_hash = hash_job_id(&job_id);
+        application.mint = None;
+        application.bond = bond;
+        application.applied_at = Clock::get()?.unix_timestamp;
+        application.rejected = false;
+        application.bump = ctx.bumps.application;
+
+        emit!(ApplicationSubmitted {
+            application: application.key(),
+            job_hash: application.job_hash,
+            recruiter,
+            freelancer: application.freelancer,
+            mint: None,
+            bond,
+            timestamp: application.applied_at,
+        });
+
+        Ok(())
+    }
+
+    /// Token variant of `apply_to_job`. The bond is held in the application's
+    /// bond vault in the job's mint; there is no on-chain minimum, since one
+    /// amount cannot fit every mint. Any Token-2022 transfer fee is withheld
+    /// from the recorded bond.
+    pub fn apply_to_token_job(
+        ctx: Context<ApplyToTokenJob>,
+        job_id: String,
+        recruiter: Pubkey,
+        bond: u64,
+    ) -> Result<()> {
+        let min_bond = ctx
+            .accounts
+            .config
+            .min_bond_for(&ctx.accounts.mint.key())
+            .ok_or(ErrorCode::BondMintNotAllowed)?;
+        require!(
+            bond > 0 && bond >= min_bond,
+            ErrorCode::InsufficientApplicationBond
+        );
+
+        token_interface::transfer_checked(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.freelancer_token_account.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.bond_vault.to_account_info(),
+                    authority: ctx.accounts.freelancer.to_account_info(),
+                },
+            ),
+            bond,
+            ctx.accounts.mint.decimals,
+        )?;
+        ctx.accounts.bond_vault.reload()?;
+
+        let application = &mut ctx.accounts.application;
+        application.recruiter = recruiter;
+        application.freelancer = ctx.accounts.freelancer.key();
+        application.job_hash = hash_job_id(&job_id);
+        application.mint = Some(ctx.accounts.mint.key());
+        application.bond = ctx.accounts.bond_vault.amount;
+        application.applied_at = Clock::get()?.unix_timestamp;
+        application.rejected = false;
+        application.bump = ctx.bumps.application;
+
+        emit!(ApplicationSubmitted {
+            application: application.key(),
+            job_hash: application.job_hash,
+            recruiter,
+            freelancer: application.freelancer,
+            mint: application.mint,
+            bond: application.bond,
+            timestamp: application.applied_at,
+        });
+
+        Ok(())
+    }
+
+    /// Recruiter turns down an applicant, who can then reclaim their bond
+    pub fn reject_application(ctx: Context<RejectApplication>) -> Result<()> {
+        let application = &mut ctx.accounts.application;
+        application.rejected = true;
+
+        emit!(ApplicationRejected {
+            application: application.key(),
+            job_hash: application.job_hash,
+            freelancer: application.freelancer,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Freelancer takes back a rejected (or long-ignored) application's SOL
+    /// bond. The bond leaves with the rest of the account when it is closed.
+    pub fn reclaim_application_bond(ctx: Context<ReclaimApplicationBond>) -> Result<()> {
+        let application = &ctx.accounts.application;
+        application.require_reclaimable(Clock::get()?.unix_timestamp)?;
+
+        emit!(ApplicationBondReclaimed {
+            application: application.key(),
+            job_hash: application.job_hash,
+            freelancer: application.freelancer,
+            bond: application.bond,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Token variant of `reclaim_application_bond`
+    pub fn reclaim_token_application_bond(
+        ctx: Context<ReclaimTokenApplicationBond>,
+    ) -> Result<()> {
+        let application = &ctx.accounts.application;
+        application.require_reclaimable(Clock::get()?.unix_timestamp)?;
+
+        emit!(ApplicationBondReclaimed {
+            application: application.key(),
+            job_hash: application.job_hash,
+            freelancer: application.freelancer,
+            bond: application.bond,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        drain_bond_vault(
+            application,
+            &ctx.accounts.bond_vault,
+            &ctx.accounts.mint,
+            &ctx.accounts.freelancer_token_account,
+            &ctx.accounts.freelancer.to_account_info(),
+            &ctx.accounts.token_program,
+        )
+    }
+
+    /// Recruiter moves the selected freelancer's SOL bond into the escrow's
+    /// vault as collateral once the job is accepted. It is returned with
+    /// `release_collateral` when the escrow settles, unless slashed first.
+    pub fn roll_application_bond(ctx: Context<RollApplicationBond>) -> Result<()> {
+        ctx.accounts.escrow.require_active()?;
+
+        let amount = ctx.accounts.application.bond;
+        transfer_lamports(
+            &ctx.accounts.application.to_account_info(),
+            &ctx.accounts.vault.to_account_info(),
+            amount,
+        )?;
+
+        let escrow = &mut ctx.accounts.escrow;
+        escrow.collateral = escrow
+            .collateral
+            .checked_add(amount)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+
+        emit!(CollateralPosted {
+            escrow: escrow.key(),
+            job_hash: escrow.job_hash,
+            application: ctx.accounts.application.key(),
+            amount,
+            collateral: escrow.collateral,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Token variant of `roll_application_bond`. Collateral is recorded as
+    /// received by the escrow vault, net of any Token-2022 transfer fee.
+    pub fn roll_token_application_bond(
+        ctx: Context<RollTokenApplicationBond>,
+    ) -> Result<()> {
+        ctx.accounts.escrow.require_active()?;
+
+        let vault_before = ctx.accounts.vault.amount;
+        drain_bond_vault(
+            &ctx.accounts.application,
+            &ctx.accounts.bond_vault,
+            &ctx.accounts.mint,
+            &ctx.accounts.vault,
+            &ctx.accounts.freelancer.to_account_info(),
+            &ctx.accounts.token_program,
+        )?;
+        ctx.accounts.vault.reload()?;
+        let amount = ctx.accounts.vault.amount - vault_before;
+
+        let escrow = &mut ctx.accounts.escrow;
+        escrow.collateral = escrow
+            .collateral
+            .checked_add(amount)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+
+        emit!(CollateralPosted {
+            escrow: escrow.key(),
+            job_hash: escrow.job_hash,
+            application: ctx.accounts.application.key(),
+            amount,
+            collateral: escrow.collateral,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Returns the collateral to the freelancer once the escrow is completed,
+    /// cancelled or terminated. Permissionless.
+    pub fn release_collateral(ctx: Context<ReleaseCollateral>) -> Result<()> {
+        let escrow = &mut ctx.accounts.escrow;
+        require!(escrow.status.is_terminal(), ErrorCode::EscrowNotSettled);
+        let amount = escrow.take_collateral()?;
+
+        transfer_from_vault(
+            &ctx.accounts.escrow,
+            &ctx.accounts.vault,
+            &ctx.accounts.freelancer.to_account_info(),
+            &ctx.accounts.system_program,
+            amount,
+        )?;
+
+        emit!(CollateralSettled {
+            escrow: ctx.accounts.escrow.key(),
+            job_hash: ctx.accounts.escrow.job_hash,
+            recipient: ctx.accounts.freelancer.key(),
+            amount,
+            slashed: false,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Token variant of `release_collateral`
+    pub fn release_token_collateral(ctx: Context<ReleaseTokenCollateral>) -> Result<()> {
+        let escrow = &mut ctx.accounts.escrow;
+        require!(escrow.status.is_terminal(), ErrorCode::EscrowNotSettled);
+        let amount = escrow.take_collateral()?;
+
+        transfer_from_token_vault(
+            &ctx.accounts.escrow,
+            &ctx.accounts.vault,
+            &ctx.accounts.mint,
+            &ctx.accounts.freelancer_token_account,
+            &ctx.accounts.token_program,
+            amount,
+        )?;
+
+        emit!(CollateralSettled {
+            escrow: ctx.accounts.escrow.key(),
+            job_hash: ctx.accounts.escrow.job_hash,
+            recipient: ctx.accounts.freelancer_token_account.key(),
+            amount,
+            slashed: false,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Recruiter forfeits a freelancer's collateral to themselves, e.g. when
+    /// the freelancer ghosts the job. Needs a `SlashCollateral` proposal that
+    /// has reached the multisig threshold, and a disputed or flagged escrow.
+    pub fn slash_collateral(ctx: Context<SlashCollateral>) -> Result<()> {
+        ctx.accounts
+            .multisig
+            .require_approved(&ctx.accounts.proposal)?;
+        let PlatformAction::SlashCollateral { escrow } = ctx.accounts.proposal.action else {
+            return err!(ErrorCode::ProposalActionMismatch);
+        };
+        require_keys_eq!(
+            escrow,
+            ctx.accounts.escrow.key(),
+            ErrorCode::ProposalActionMismatch
+        );
+
+        let escrow = &mut ctx.accounts.escrow;
+        require!(
+            escrow.status.allows_platform_action(),
+            ErrorCode::PlatformActionNotAllowed
+        );
+        let amount = escrow.take_collateral()?;
+
+        transfer_from_vault(
+            &ctx.accounts.escrow,
+            &ctx.accounts.vault,
+            &ctx.accounts.recruiter.to_account_info(),
+            &ctx.accounts.system_program,
+            amount,
+        )?;
+
+        emit!(CollateralSettled {
+            escrow: ctx.accounts.escrow.key(),
+            job_hash: ctx.accounts.escrow.job_hash,
+            recipient: ctx.accounts.recruiter.key(),
+            amount,
+            slashed: true,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Token variant of `slash_collateral`
+    pub fn slash_token_collateral(ctx: Context<SlashTokenCollateral>) -> Result<()> {
+        ctx.accounts
+            .multisig
+            .require_approved(&ctx.accounts.proposal)?;
+        let PlatformAction::SlashCollateral { escrow } = ctx.accounts.proposal.action else {
+            return err!(ErrorCode::ProposalActionMismatch);
+        };
+        require_keys_eq!(
+            escrow,
+            ctx.accounts.escrow.key(),
+            ErrorCode::ProposalActionMismatch
+        );
+
+        let escrow = &mut ctx.accounts.escrow;
+        require!(
+            escrow.status.allows_platform_action(),
+            ErrorCode::PlatformActionNotAllowed
+        );
+        let amount = escrow.take_collateral()?;
+
+        transfer_from_token_vault(
+            &ctx.accounts.escrow,
+            &ctx.accounts.vault,
+            &ctx.accounts.mint,
+            &ctx.accounts.recruiter_token_account,
+            &ctx.accounts.token_program,
+            amount,
+        )?;
+
+        emit!(CollateralSettled {
+            escrow: ctx.accounts.escrow.key(),
+            job_hash: ctx.accounts.escrow.job_hash,
+            recipient: ctx.accounts.recruiter_token_account.key(),
+            amount,
+            slashed: true,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(job_id: String, freelancer: Pubkey, milestone_amounts: Vec<u64>)]
+pub struct CreateJobEscrow<'info> {
+    #[account(
+        init_if_needed,
+        payer = recruiter,
+        space = 8 + JobCounter::INIT_SPACE,
+        seeds = [b"job", recruiter.key().as_ref(), &hash_job_id(&job_id)],
+        bump
+    )]
+    pub job_counter: Account<'info, JobCounter>,
+
+    #[account(
+        init,
+        payer = recruiter,
+        space = Escrow::space(milestone_amounts.len()),
+        seeds = [
+            b"escrow",
+            recruiter.key().as_ref(),
+            &hash_job_id(&job_id),
+            &job_counter.escrow_count.to_le_bytes()
+        ],
+        bump
+    )]
+    pub escrow: Account<'info, Escrow>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", escrow.key().as_ref()],
+        bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused.creates @ ErrorCode::PlatformPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(mut)]
+    pub recruiter: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptJob<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"escrow",
+            escrow.recruiter.as_ref(),
+            escrow.job_hash.as_ref(),
+            &escrow.generation_seed()
+        ],
+        bump = escrow.bump,
+        has_one = freelancer,
+        constraint = escrow.mint.is_none() @ ErrorCode::InvalidEscrowMint
+    )]
+    pub escrow: Account<'info, Escrow>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", escrow.key().as_ref()],
+        bump = escrow.vault_bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused.claims @ ErrorCode::PlatformPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(mut, seeds = [b"treasury"], bump = fee_treasury.bump)]
+    pub fee_treasury: Account<'info, Treasury>,
+
+    #[account(mut)]
+    pub freelancer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ApproveMilestone<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"escrow",
+            escrow.recruiter.as_ref(),
+            escrow.job_hash.as_ref(),
+            &escrow.generation_seed()
+        ],
+        bump = escrow.bump,
+        has_one = recruiter
+    )]
+    pub escrow: Account<'info, Escrow>,
+
+    /// Bump derived rather than read from the escrow: a version 0 escrow has
+    /// no vault until `upgrade_legacy_escrow` creates it
+    #[account(
+        mut,
+        seeds = [b"vault", escrow.key().as_ref()],
+        bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    /// Pays the extra rent if a version 0 escrow is upgraded
+    #[account(mut)]
+    pub recruiter: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SubmitMilestone<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"escrow",
+            escrow.recruiter.as_ref(),
+            escrow.job_hash.as_ref(),
+            &escrow.generation_seed()
+        ],
+        bump = escrow.bump,
+        has_one = freelancer
+    )]
+    pub escrow: Account<'info, Escrow>,
+
+    pub freelancer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RejectMilestone<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"escrow",
+            escrow.recruiter.as_ref(),
+            escrow.job_hash.as_ref(),
+            &escrow.generation_seed()
+        ],
+        bump = escrow.bump,
+        has_one = recruiter
+    )]
+    pub escrow: Account<'info, Escrow>,
+
+    pub recruiter: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AutoApproveMilestone<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"escrow",
+            escrow.recruiter.as_ref(),
+            escrow.job_hash.as_ref(),
+            &escrow.generation_seed()
+        ],
+        bump = escrow.bump
+    )]
+    pub escrow: Account<'info, Escrow>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimMilestone<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"escrow",
+            escrow.recruiter.as_ref(),
+            escrow.job_hash.as_ref(),
+            &escrow.generation_seed()
+        ],
+        bump = escrow.bump,
+        has_one = freelancer,
+        constraint = escrow.mint.is_none() @ ErrorCode::InvalidEscrowMint
+    )]
+    pub escrow: Account<'info, Escrow>,
+
+    /// Bump derived rather than read from the escrow: a version 0 escrow has
+    /// no vault until `upgrade_legacy_escrow` creates it
+    #[account(
+        mut,
+        seeds = [b"vault", escrow.key().as_ref()],
+        bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused.claims @ ErrorCode::PlatformPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(mut, seeds = [b"treasury"], bump = fee_treasury.bump)]
+    pub fee_treasury: Account<'info, Treasury>,
+
+    #[account(mut)]
+    pub freelancer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelJob<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"escrow",
+            escrow.recruiter.as_ref(),
+            escrow.job_hash.as_ref(),
+            &escrow.generation_seed()
+        ],
+        bump = escrow.bump,
+        has_one = recruiter,
+        constraint = escrow.mint.is_none() @ ErrorCode::InvalidEscrowMint
+    )]
+    pub escrow: Account<'info, Escrow>,
+
+    /// Bump derived rather than read from the escrow: a version 0 escrow has
+    /// no vault until `upgrade_legacy_escrow` creates it
+    #[account(
+        mut,
+        seeds = [b"vault", escrow.key().as_ref()],
+        bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused.cancellations @ ErrorCode::PlatformPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(mut)]
+    pub recruiter: Signer<'info>,
+
+    /// Required once the freelancer has accepted: a binding escrow is only
+    /// cancelled by mutual consent (or through a dispute)
+    #[account(address = escrow.freelancer @ ErrorCode::NotEscrowParty)]
+    pub freelancer: Option<Signer<'info>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RefundUnacceptedJob<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"escrow",
+            escrow.recruiter.as_ref(),
+            escrow.job_hash.as_ref(),
+            &escrow.generation_seed()
+        ],
+        bump = escrow.bump,
+        has_one = recruiter,
+        constraint = escrow.mint.is_none() @ ErrorCode::InvalidEscrowMint
+    )]
+    pub escrow: Account<'info, Escrow>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", escrow.key().as_ref()],
+        bump = escrow.vault_bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused.cancellations @ ErrorCode::PlatformPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    /// CHECK: receives the refund; checked by `has_one`
+    #[account(mut)]
+    pub recruiter: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseCompletedEscrow<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"escrow",
+            escrow.recruiter.as_ref(),
+            escrow.job_hash.as_ref(),
+            &escrow.generation_seed()
+        ],
+        bump = escrow.bump,
+        has_one = recruiter,
+        constraint = escrow.mint.is_none() @ ErrorCode::InvalidEscrowMint,
+        close = recruiter
+    )]
+    pub escrow: Account<'info, Escrow>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", escrow.key().as_ref()],
+        bump = escrow.vault_bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    /// CHECK: rent payer receiving the escrow rent; checked by `has_one`
+    #[account(mut)]
+    pub recruiter: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseCompletedTokenEscrow<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"escrow",
+            escrow.recruiter.as_ref(),
+            escrow.job_hash.as_ref(),
+            &escrow.generation_seed()
+        ],
+        bump = escrow.bump,
+        has_one = recruiter,
+        constraint = escrow.mint == Some(mint.key()) @ ErrorCode::InvalidEscrowMint,
+        close = recruiter
+    )]
+    pub escrow: Account<'info, Escrow>,
+
+    /// Writable so withheld transfer fees can be harvested before closing
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"token_vault", escrow.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = escrow,
+        token::token_program = token_program
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = recruiter,
+        token::token_program = token_program
+    )]
+    pub recruiter_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: rent payer receiving the escrow and vault rent; checked by `has_one`
+    #[account(mut)]
+    pub recruiter: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+#[instruction(milestone_amounts: Vec<u64>)]
+pub struct AmendEscrow<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"escrow",
+            escrow.recruiter.as_ref(),
+            escrow.job_hash.as_ref(),
+            &escrow.generation_seed()
+        ],
+        bump = escrow.bump,
+        has_one = recruiter,
+        has_one = freelancer,
+        constraint = escrow.mint.is_none() @ ErrorCode::InvalidEscrowMint,
+        realloc = Escrow::space(milestone_amounts.len()),
+        realloc::payer = recruiter,
+        realloc::zero = false
+    )]
+    pub escrow: Account<'info, Escrow>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", escrow.key().as_ref()],
+        bump = escrow.vault_bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused.amendments @ ErrorCode::PlatformPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(mut)]
+    pub recruiter: Signer<'info>,
+
+    pub freelancer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(milestone_amounts: Vec<u64>)]
+pub struct AmendTokenEscrow<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"escrow",
+            escrow.recruiter.as_ref(),
+            escrow.job_hash.as_ref(),
+            &escrow.generation_seed()
+        ],
+        bump = escrow.bump,
+        has_one = recruiter,
+        has_one = freelancer,
+        constraint = escrow.mint == Some(mint.key()) @ ErrorCode::InvalidEscrowMint,
+        realloc = Escrow::space(milestone_amounts.len()),
+        realloc::payer = recruiter,
+        realloc::zero = false
+    )]
+    pub escrow: Account<'info, Escrow>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"token_vault", escrow.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = escrow,
+        token::token_program = token_program
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = recruiter,
+        token::token_program = token_program
+    )]
+    pub recruiter_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused.amendments @ ErrorCode::PlatformPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(mut)]
+    pub recruiter: Signer<'info>,
+
+    pub freelancer: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(milestone_amounts: Vec<u64>)]
+pub struct AddMilestones<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"escrow",
+            escrow.recruiter.as_ref(),
+            escrow.job_hash.as_ref(),
+            &escrow.generation_seed()
+        ],
+        bump = escrow.bump,
+        has_one = recruiter,
+        constraint = escrow.mint.is_none() @ ErrorCode::InvalidEscrowMint,
+        realloc = Escrow::space(escrow.milestone_amounts.len() + milestone_amounts.len()),
+        realloc::payer = recruiter,
+        realloc::zero = false
+    )]
+    pub escrow: Account<'info, Escrow>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", escrow.key().as_ref()],
+        bump = escrow.vault_bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused.amendments @ ErrorCode::PlatformPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(mut)]
+    pub recruiter: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(milestone_amounts: Vec<u64>)]
+pub struct AddTokenMilestones<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"escrow",
+            escrow.recruiter.as_ref(),
+            escrow.job_hash.as_ref(),
+            &escrow.generation_seed()
+        ],
+        bump = escrow.bump,
+        has_one = recruiter,
+        constraint = escrow.mint == Some(mint.key()) @ ErrorCode::InvalidEscrowMint,
+        realloc = Escrow::space(escrow.milestone_amounts.len() + milestone_amounts.len()),
+        realloc::payer = recruiter,
+        realloc::zero = false
+    )]
+    pub escrow: Account<'info, Escrow>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"token_vault", escrow.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = escrow,
+        token::token_program = token_program
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = recruiter,
+        token::token_program = token_program
+    )]
+    pub recruiter_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused.amendments @ ErrorCode::PlatformPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(mut)]
+    pub recruiter: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RefundMilestoneRemainder<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"escrow",
+            escrow.recruiter.as_ref(),
+            escrow.job_hash.as_ref(),
+            &escrow.generation_seed()
+        ],
+        bump = escrow.bump,
+        has_one = recruiter,
+        constraint = escrow.mint.is_none() @ ErrorCode::InvalidEscrowMint
+    )]
+    pub escrow: Account<'info, Escrow>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", escrow.key().as_ref()],
+        bump = escrow.vault_bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused.cancellations @ ErrorCode::PlatformPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(mut)]
+    pub recruiter: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RefundTokenMilestoneRemainder<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"escrow",
+            escrow.recruiter.as_ref(),
+            escrow.job_hash.as_ref(),
+            &escrow.generation_seed()
+        ],
+        bump = escrow.bump,
+        has_one = recruiter,
+        constraint = escrow.mint == Some(mint.key()) @ ErrorCode::InvalidEscrowMint
+    )]
+    pub escrow: Account<'info, Escrow>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"token_vault", escrow.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = escrow,
+        token::token_program = token_program
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = recruiter,
+        token::token_program = token_program
+    )]
+    pub recruiter_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused.cancellations @ ErrorCode::PlatformPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    pub recruiter: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct ReclaimExpiredMilestones<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"escrow",
+            escrow.recruiter.as_ref(),
+            escrow.job_hash.as_ref(),
+            &escrow.generation_seed()
+        ],
+        bump = escrow.bump,
+        has_one = recruiter,
+        constraint = escrow.mint.is_none() @ ErrorCode::InvalidEscrowMint
+    )]
+    pub escrow: Account<'info, Escrow>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", escrow.key().as_ref()],
+        bump = escrow.vault_bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused.cancellations @ ErrorCode::PlatformPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(mut)]
+    pub recruiter: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ReclaimExpiredTokenMilestones<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"escrow",
+            escrow.recruiter.as_ref(),
+            escrow.job_hash.as_ref(),
+            &escrow.generation_seed()
+        ],
+        bump = escrow.bump,
+        has_one = recruiter,
+        constraint = escrow.mint == Some(mint.key()) @ ErrorCode::InvalidEscrowMint
+    )]
+    pub escrow: Account<'info, Escrow>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"token_vault", escrow.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = escrow,
+        token::token_program = token_program
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = recruiter,
+        token::token_program = token_program
+    )]
+    pub recruiter_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused.cancellations @ ErrorCode::PlatformPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    pub recruiter: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+#[instruction(job_id: String, freelancer: Pubkey, milestone_amounts: Vec<u64>)]
+pub struct CreateTokenJobEscrow<'info> {
+    #[account(
+        init_if_needed,
+        payer = recruiter,
+        space = 8 + JobCounter::INIT_SPACE,
+        seeds = [b"job", recruiter.key().as_ref(), &hash_job_id(&job_id)],
+        bump
+    )]
+    pub job_counter: Account<'info, JobCounter>,
+
+    #[account(
+        init,
+        payer = recruiter,
+        space = Escrow::space(milestone_amounts.len()),
+        seeds = [
+            b"escrow",
+            recruiter.key().as_ref(),
+            &hash_job_id(&job_id),
+            &job_counter.escrow_count.to_le_bytes()
+        ],
+        bump
+    )]
+    pub escrow: Account<'info, Escrow>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused.creates @ ErrorCode::PlatformPaused
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = recruiter,
+        seeds = [b"token_vault", escrow.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = escrow,
+        token::token_program = token_program
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = recruiter,
+        token::token_program = token_program
+    )]
+    pub recruiter_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub recruiter: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptTokenJob<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"escrow",
+            escrow.recruiter.as_ref(),
+            escrow.job_hash.as_ref(),
+            &escrow.generation_seed()
+        ],
+        bump = escrow.bump,
+        has_one = freelancer,
+        constraint = escrow.mint == Some(mint.key()) @ ErrorCode::InvalidEscrowMint
+    )]
+    pub escrow: Account<'info, Escrow>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused.claims @ ErrorCode::PlatformPaused
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(seeds = [b"treasury"], bump = fee_treasury.bump)]
+    pub fee_treasury: Box<Account<'info, Treasury>>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"token_vault", escrow.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = escrow,
+        token::token_program = token_program
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = freelancer,
+        associated_token::mint = mint,
+        associated_token::authority = freelancer,
+        associated_token::token_program = token_program
+    )]
+    pub freelancer_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = freelancer,
+        associated_token::mint = mint,
+        associated_token::authority = fee_treasury,
+        associated_token::token_program = token_program
+    )]
+    pub fee_treasury_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub freelancer: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimTokenMilestone<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"escrow",
+            escrow.recruiter.as_ref(),
+            escrow.job_hash.as_ref(),
+            &escrow.generation_seed()
+        ],
+        bump = escrow.bump,
+        has_one = freelancer,
+        constraint = escrow.mint == Some(mint.key()) @ ErrorCode::InvalidEscrowMint
+    )]
+    pub escrow: Account<'info, Escrow>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused.claims @ ErrorCode::PlatformPaused
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(seeds = [b"treasury"], bump = fee_treasury.bump)]
+    pub fee_treasury: Box<Account<'info, Treasury>>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"token_vault", escrow.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = escrow,
+        token::token_program = token_program
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = freelancer,
+        associated_token::mint = mint,
+        associated_token::authority = freelancer,
+        associated_token::token_program = token_program
+    )]
+    pub freelancer_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = freelancer,
+        associated_token::mint = mint,
+        associated_token::authority = fee_treasury,
+        associated_token::token_program = token_program
+    )]
+    pub fee_treasury_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub freelancer: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelTokenJob<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"escrow",
+            escrow.recruiter.as_ref(),
+            escrow.job_hash.as_ref(),
+            &escrow.generation_seed()
+        ],
+        bump = escrow.bump,
+        has_one = recruiter,
+        constraint = escrow.mint == Some(mint.key()) @ ErrorCode::InvalidEscrowMint
+    )]
+    pub escrow: Account<'info, Escrow>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused.cancellations @ ErrorCode::PlatformPaused
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"token_vault", escrow.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = escrow,
+        token::token_program = token_program
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = recruiter,
+        token::token_program = token_program
+    )]
+    pub recruiter_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub recruiter: Signer<'info>,
+
+    /// Required once the freelancer has accepted: a binding escrow is only
+    /// cancelled by mutual consent (or through a dispute)
+    #[account(address = escrow.freelancer @ ErrorCode::NotEscrowParty)]
+    pub freelancer: Option<Signer<'info>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct RefundUnacceptedTokenJob<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"escrow",
+            escrow.recruiter.as_ref(),
+            escrow.job_hash.as_ref(),
+            &escrow.generation_seed()
+        ],
+        bump = escrow.bump,
+        has_one = recruiter,
+        constraint = escrow.mint == Some(mint.key()) @ ErrorCode::InvalidEscrowMint
+    )]
+    pub escrow: Account<'info, Escrow>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused.cancellations @ ErrorCode::PlatformPaused
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"token_vault", escrow.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = escrow,
+        token::token_program = token_program
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = recruiter,
+        token::token_program = token_program
+    )]
+    pub recruiter_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: owner of the refund token account; checked by `has_one`
+    pub recruiter: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateEscrow<'info> {
+    /// CHECK: still in the version 0 layout, so it cannot be deserialized as
+    /// `Escrow`; owner is checked here, discriminator and size in the handler
+    #[account(mut, owner = crate::ID)]
+    pub escrow: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", escrow.key().as_ref()],
+        bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + Config::INIT_SPACE,
+        seeds = [b"config"],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + Treasury::INIT_SPACE,
+        seeds = [b"treasury"],
+        bump
+    )]
+    pub fee_treasury: Account<'info, Treasury>,
+
+    #[account(
+        constraint = program.programdata_address()? == Some(program_data.key())
+    )]
+    pub program: Program<'info, crate::program::FreelancePlatform>,
+
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(payer.key())
+            @ ErrorCode::UnauthorizedPlatformAccess
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = authority @ ErrorCode::UnauthorizedPlatformAccess
+    )]
+    pub config: Account<'info, Config>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeAuthorityTransfer<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = authority @ ErrorCode::UnauthorizedPlatformAccess
+    )]
+    pub config: Account<'info, Config>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAuthorityTransfer<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.pending_authority == Some(new_authority.key())
+            @ ErrorCode::NotPendingAuthority
+    )]
+    pub config: Account<'info, Config>,
+
+    pub new_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeMultisig<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Multisig::INIT_SPACE,
+        seeds = [b"multisig"],
+        bump
+    )]
+    pub multisig: Account<'info, Multisig>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = authority @ ErrorCode::UnauthorizedPlatformAccess
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ProposePlatformAction<'info> {
+    #[account(mut, seeds = [b"multisig"], bump = multisig.bump)]
+    pub multisig: Account<'info, Multisig>,
+
+    #[account(
+        init,
+        payer = proposer,
+        space = 8 + Proposal::INIT_SPACE,
+        seeds = [
+            b"proposal",
+            multisig.key().as_ref(),
+            &multisig.proposal_count.to_le_bytes()
+        ],
+        bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    /// The escrow an `EmergencyClose`, `SlashCollateral` or `FlagEscrow`
+    /// action targets
+    pub escrow: Option<Account<'info, Escrow>>,
+
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ApproveProposal<'info> {
+    #[account(seeds = [b"multisig"], bump = multisig.bump)]
+    pub multisig: Account<'info, Multisig>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"proposal",
+            multisig.key().as_ref(),
+            &proposal.index.to_le_bytes()
+        ],
+        bump = proposal.bump,
+        has_one = multisig
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CancelProposal<'info> {
+    #[account(seeds = [b"multisig"], bump = multisig.bump)]
+    pub multisig: Account<'info, Multisig>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"proposal",
+            multisig.key().as_ref(),
+            &proposal.index.to_le_bytes()
+        ],
+        bump = proposal.bump,
+        has_one = multisig,
+        has_one = proposer,
+        close = proposer
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    /// CHECK: receives the proposal rent; checked by `has_one`
+    #[account(mut)]
+    pub proposer: UncheckedAccount<'info>,
+
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetMultisigOwners<'info> {
+    #[account(mut, seeds = [b"multisig"], bump = multisig.bump)]
+    pub multisig: Account<'info, Multisig>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"proposal",
+            multisig.key().as_ref(),
+            &proposal.index.to_le_bytes()
+        ],
+        bump = proposal.bump,
+        has_one = multisig,
+        has_one = proposer,
+        close = proposer
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    /// CHECK: receives the proposal rent; checked by `has_one`
+    #[account(mut)]
+    pub proposer: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetFees<'info> {
+    #[account(mut, seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    #[account(seeds = [b"multisig"], bump = multisig.bump)]
+    pub multisig: Account<'info, Multisig>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"proposal",
+            multisig.key().as_ref(),
+            &proposal.index.to_le_bytes()
+        ],
+        bump = proposal.bump,
+        has_one = multisig,
+        has_one = proposer,
+        close = proposer
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    /// CHECK: receives the proposal rent; checked by `has_one`
+    #[account(mut)]
+    pub proposer: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawFees<'info> {
+    #[account(seeds = [b"config"], bump = config.bump, has_one = treasury)]
+    pub config: Account<'info, Config>,
+
+    #[account(mut, seeds = [b"treasury"], bump = fee_treasury.bump)]
+    pub fee_treasury: Account<'info, Treasury>,
+
+    /// CHECK: treasury wallet receiving the fees; checked by `has_one`
+    #[account(mut)]
+    pub treasury: UncheckedAccount<'info>,
+
+    #[account(seeds = [b"multisig"], bump = multisig.bump)]
+    pub multisig: Account<'info, Multisig>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"proposal",
+            multisig.key().as_ref(),
+            &proposal.index.to_le_bytes()
+        ],
+        bump = proposal.bump,
+        has_one = multisig,
+        has_one = proposer,
+        close = proposer
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    /// CHECK: receives the proposal rent; checked by `has_one`
+    #[account(mut)]
+    pub proposer: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawTokenFees<'info> {
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    #[account(seeds = [b"treasury"], bump = fee_treasury.bump)]
+    pub fee_treasury: Account<'info, Treasury>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = fee_treasury,
+        associated_token::token_program = token_program
+    )]
+    pub fee_treasury_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = config.treasury,
+        token::token_program = token_program
+    )]
+    pub treasury_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(seeds = [b"multisig"], bump = multisig.bump)]
+    pub multisig: Account<'info, Multisig>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"proposal",
+            multisig.key().as_ref(),
+            &proposal.index.to_le_bytes()
+        ],
+        bump = proposal.bump,
+        has_one = multisig,
+        has_one = proposer,
+        close = proposer
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    /// CHECK: receives the proposal rent; checked by `has_one`
+    #[account(mut)]
+    pub proposer: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct FlagEscrow<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"escrow",
+            escrow.recruiter.as_ref(),
+            escrow.job_hash.as_ref(),
+            &escrow.generation_seed()
+        ],
+        bump = escrow.bump
+    )]
+    pub escrow: Account<'info, Escrow>,
+
+    #[account(seeds = [b"multisig"], bump = multisig.bump)]
+    pub multisig: Account<'info, Multisig>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"proposal",
+            multisig.key().as_ref(),
+            &proposal.index.to_le_bytes()
+        ],
+        bump = proposal.bump,
+        has_one = multisig,
+        has_one = proposer,
+        close = proposer
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    /// CHECK: receives the proposal rent; checked by `has_one`
+    #[account(mut)]
+    pub proposer: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UnflagEscrow<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"escrow",
+            escrow.recruiter.as_ref(),
+            escrow.job_hash.as_ref(),
+            &escrow.generation_seed()
+        ],
+        bump = escrow.bump
+    )]
+    pub escrow: Account<'info, Escrow>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = authority @ ErrorCode::UnauthorizedPlatformAccess
+    )]
+    pub config: Account<'info, Config>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct PlatformEmergencyClose<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"escrow",
+            escrow.recruiter.as_ref(),
+            escrow.job_hash.as_ref(),
+            &escrow.generation_seed()
+        ],
+        bump = escrow.bump,
+        has_one = freelancer,
+        has_one = recruiter,
+        constraint = escrow.mint.is_none() @ ErrorCode::InvalidEscrowMint
+    )]
+    pub escrow: Account<'info, Escrow>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", escrow.key().as_ref()],
+        bump = escrow.vault_bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    /// Required when the escrow is Disputed. The platform overrides the
+    /// arbitration, so the dispute is closed and the bond refunded to its opener.
+    #[account(
+        mut,
+        seeds = [b"dispute", escrow.key().as_ref()],
+        bump = dispute.bump,
+        has_one = opened_by,
+        close = opened_by
+    )]
+    pub dispute: Option<Account<'info, Dispute>>,
+
+    #[account(seeds = [b"multisig"], bump = multisig.bump)]
+    pub multisig: Account<'info, Multisig>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"proposal",
+            multisig.key().as_ref(),
+            &proposal.index.to_le_bytes()
+        ],
+        bump = proposal.bump,
+        has_one = multisig,
+        has_one = proposer,
+        close = proposer
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(mut, seeds = [b"treasury"], bump = fee_treasury.bump)]
+    pub fee_treasury: Account<'info, Treasury>,
+
+    /// CHECK: receives the approved funds; checked by `has_one`
+    #[account(mut)]
+    pub freelancer: UncheckedAccount<'info>,
+
+    /// CHECK: receives the unapproved funds; checked by `has_one`
+    #[account(mut)]
+    pub recruiter: UncheckedAccount<'info>,
+
+    /// CHECK: receives the dispute rent and bond; checked by `has_one`
+    #[account(mut)]
+    pub opened_by: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: receives the proposal rent; checked by `has_one`
+    #[account(mut)]
+    pub proposer: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct PlatformEmergencyCloseToken<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"escrow",
+            escrow.recruiter.as_ref(),
+            escrow.job_hash.as_ref(),
+            &escrow.generation_seed()
+        ],
+        bump = escrow.bump,
+        has_one = freelancer,
+        has_one = recruiter,
+        constraint = escrow.mint == Some(mint.key()) @ ErrorCode::InvalidEscrowMint
+    )]
+    pub escrow: Box<Account<'info, Escrow>>,
+
+    /// Writable so withheld transfer fees can be harvested before closing
+    #[account(mut)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [b"token_vault", escrow.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = escrow,
+        token::token_program = token_program
+    )]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Required when the escrow is Disputed. The platform overrides the
+    /// arbitration, so the dispute is closed and the bond refunded to its opener.
+    #[account(
+        mut,
+        seeds = [b"dispute", escrow.key().as_ref()],
+        bump = dispute.bump,
+        has_one = opened_by,
+        close = opened_by
+    )]
+    pub dispute: Option<Box<Account<'info, Dispute>>>,
+
+    #[account(seeds = [b"multisig"], bump = multisig.bump)]
+    pub multisig: Box<Account<'info, Multisig>>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"proposal",
+            multisig.key().as_ref(),
+            &proposal.index.to_le_bytes()
+        ],
+        bump = proposal.bump,
+        has_one = multisig,
+        has_one = proposer,
+        close = proposer
+    )]
+    pub proposal: Box<Account<'info, Proposal>>,
+
+    #[account(seeds = [b"treasury"], bump = fee_treasury.bump)]
+    pub fee_treasury: Box<Account<'info, Treasury>>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = mint,
+        associated_token::authority = fee_treasury,
+        associated_token::token_program = token_program
+    )]
+    pub fee_treasury_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = mint,
+        associated_token::authority = freelancer,
+        associated_token::token_program = token_program
+    )]
+    pub freelancer_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = recruiter,
+        token::token_program = token_program
+    )]
+    pub recruiter_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: owner of the freelancer token account; checked by `has_one`
+    pub freelancer: UncheckedAccount<'info>,
+
+    /// CHECK: receives the vault and escrow rent; checked by `has_one`
+    #[account(mut)]
+    pub recruiter: UncheckedAccount<'info>,
+
+    /// CHECK: receives the dispute rent and bond; checked by `has_one`
+    #[account(mut)]
+    pub opened_by: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: receives the proposal rent; checked by `has_one`
+    #[account(mut)]
+    pub proposer: UncheckedAccount<'info>,
+
+    /// Pays for the freelancer and fee treasury token accounts if needed
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct OpenDispute<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"escrow",
+            escrow.recruiter.as_ref(),
+            escrow.job_hash.as_ref(),
+            &escrow.generation_seed()
+        ],
+        bump = escrow.bump,
+        constraint = disputant.key() == escrow.recruiter
+            || disputant.key() == escrow.freelancer @ ErrorCode::NotEscrowParty
+    )]
+    pub escrow: Account<'info, Escrow>,
+
+    #[account(
+        init,
+        payer = disputant,
+        space = 8 + Dispute::INIT_SPACE,
+        seeds = [b"dispute", escrow.key().as_ref()],
+        bump
+    )]
+    pub dispute: Account<'info, Dispute>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    #[account(mut)]
+    pub disputant: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ResolveDispute<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"escrow",
+            escrow.recruiter.as_ref(),
+            escrow.job_hash.as_ref(),
+            &escrow.generation_seed()
+        ],
+        bump = escrow.bump,
+        has_one = recruiter,
+        has_one = freelancer,
+        constraint = escrow.mint.is_none() @ ErrorCode::InvalidEscrowMint
+    )]
+    pub escrow: Account<'info, Escrow>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", escrow.key().as_ref()],
+        bump = escrow.vault_bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"dispute", escrow.key().as_ref()],
+        bump = dispute.bump,
+        has_one = arbitrator @ ErrorCode::UnauthorizedArbitrator,
+        has_one = opened_by,
+        close = opened_by
+    )]
+    pub dispute: Account<'info, Dispute>,
+
+    #[account(seeds = [b"config"], bump = config.bump, has_one = treasury)]
+    pub config: Account<'info, Config>,
+
+    /// CHECK: receives the recruiter's share; checked by `has_one`
+    #[account(mut)]
+    pub recruiter: UncheckedAccount<'info>,
+
+    /// CHECK: receives the freelancer's share; checked by `has_one`
+    #[account(mut)]
+    pub freelancer: UncheckedAccount<'info>,
+
+    /// CHECK: receives the dispute rent and a refunded bond; checked by `has_one`
+    #[account(mut)]
+    pub opened_by: UncheckedAccount<'info>,
+
+    /// CHECK: receives a forfeited bond; checked against config
+    #[account(mut)]
+    pub treasury: UncheckedAccount<'info>,
+
+    pub arbitrator: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ResolveTokenDispute<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"escrow",
+            escrow.recruiter.as_ref(),
+            escrow.job_hash.as_ref(),
+            &escrow.generation_seed()
+        ],
+        bump = escrow.bump,
+        has_one = recruiter,
+        has_one = freelancer,
+        constraint = escrow.mint == Some(mint.key()) @ ErrorCode::InvalidEscrowMint
+    )]
+    pub escrow: Box<Account<'info, Escrow>>,
+
+    #[account(
+        mut,
+        seeds = [b"dispute", escrow.key().as_ref()],
+        bump = dispute.bump,
+        has_one = arbitrator @ ErrorCode::UnauthorizedArbitrator,
+        has_one = opened_by,
+        close = opened_by
+    )]
+    pub dispute: Box<Account<'info, Dispute>>,
+
+    #[account(seeds = [b"config"], bump = config.bump, has_one = treasury)]
+    pub config: Box<Account<'info, Config>>,
+
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [b"token_vault", escrow.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = escrow,
+        token::token_program = token_program
+    )]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = recruiter,
+        token::token_program = token_program
+    )]
+    pub recruiter_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = arbitrator,
+        associated_token::mint = mint,
+        associated_token::authority = freelancer,
+        associated_token::token_program = token_program
+    )]
+    pub freelancer_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: owner of the recruiter token account; checked by `has_one`
+    pub recruiter: UncheckedAccount<'info>,
+
+    /// CHECK: owner of the freelancer token account; checked by `has_one`
+    pub freelancer: UncheckedAccount<'info>,
+
+    /// CHECK: receives the dispute rent and a refunded bond; checked by `has_one`
+    #[account(mut)]
+    pub opened_by: UncheckedAccount<'info>,
+
+    /// CHECK: receives a forfeited bond; checked against config
+    #[account(mut)]
+    pub treasury: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub arbitrator: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(job_id: String, recruiter: Pubkey)]
+pub struct ApplyToJob<'info> {
+    #[account(
+        init,
+        payer = freelancer,
+        space = 8 + Application::INIT_SPACE,
+        seeds = [
+            b"application",
+            recruiter.as_ref(),
+            &hash_job_id(&job_id),
+            freelancer.key().as_ref()
+        ],
+        bump
+    )]
+    pub application: Account<'info, Application>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    #[account(mut)]
+    pub freelancer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(job_id: String, recruiter: Pubkey)]
+pub struct ApplyToTokenJob<'info> {
+    #[account(
+        init,
+        payer = freelancer,
+        space = 8 + Application::INIT_SPACE,
+        seeds = [
+            b"application",
+            recruiter.as_ref(),
+            &hash_job_id(&job_id),
+            freelancer.key().as_ref()
+        ],
+        bump
+    )]
+    pub application: Account<'info, Application>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        init,
+        payer = freelancer,
+        seeds = [b"bond_vault", application.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = application,
+        token::token_program = token_program
+    )]
+    pub bond_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = freelancer,
+        token::token_program = token_program
+    )]
+    pub freelancer_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub freelancer: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RejectApplication<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"application",
+            application.recruiter.as_ref(),
+            application.job_hash.as_ref(),
+            application.freelancer.as_ref()
+        ],
+        bump = application.bump,
+        has_one = recruiter
+    )]
+    pub application: Account<'info, Application>,
+
+    pub recruiter: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ReclaimApplicationBond<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"application",
+            application.recruiter.as_ref(),
+            application.job_hash.as_ref(),
+            application.freelancer.as_ref()
+        ],
+        bump = application.bump,
+        has_one = freelancer,
+        constraint = application.mint.is_none() @ ErrorCode::InvalidEscrowMint,
+        close = freelancer
+    )]
+    pub application: Account<'info, Application>,
+
+    #[account(mut)]
+    pub freelancer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ReclaimTokenApplicationBond<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"application",
+            application.recruiter.as_ref(),
+            application.job_hash.as_ref(),
+            application.freelancer.as_ref()
+        ],
+        bump = application.bump,
+        has_one = freelancer,
+        constraint = application.mint == Some(mint.key()) @ ErrorCode::InvalidEscrowMint,
+        close = freelancer
+    )]
+    pub application: Account<'info, Application>,
+
+    /// Writable so withheld transfer fees can be harvested before closing
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"bond_vault", application.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = application,
+        token::token_program = token_program
+    )]
+    pub bond_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = freelancer,
+        token::token_program = token_program
+    )]
+    pub freelancer_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub freelancer: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct RollApplicationBond<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"escrow",
+            escrow.recruiter.as_ref(),
+            escrow.job_hash.as_ref(),
+            &escrow.generation_seed()
+        ],
+        bump = escrow.bump,
+        has_one = recruiter,
+        has_one = freelancer,
+        constraint = escrow.mint.is_none() @ ErrorCode::InvalidEscrowMint
+    )]
+    pub escrow: Account<'info, Escrow>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", escrow.key().as_ref()],
+        bump = escrow.vault_bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"application",
+            application.recruiter.as_ref(),
+            application.job_hash.as_ref(),
+            application.freelancer.as_ref()
+        ],
+        bump = application.bump,
+        has_one = recruiter,
+        has_one = freelancer,
+        constraint = application.job_hash == escrow.job_hash @ ErrorCode::ApplicationMismatch,
+        constraint = application.mint.is_none() @ ErrorCode::InvalidEscrowMint,
+        constraint = !application.rejected @ ErrorCode::ApplicationRejected,
+        close = freelancer
+    )]
+    pub application: Account<'info, Application>,
+
+    pub recruiter: Signer<'info>,
+
+    /// CHECK: receives the application rent; checked by `has_one`
+    #[account(mut)]
+    pub freelancer: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RollTokenApplicationBond<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"escrow",
+            escrow.recruiter.as_ref(),
+            escrow.job_hash.as_ref(),
+            &escrow.generation_seed()
+        ],
+        bump = escrow.bump,
+        has_one = recruiter,
+        has_one = freelancer,
+        constraint = escrow.mint == Some(mint.key()) @ ErrorCode::InvalidEscrowMint
+    )]
+    pub escrow: Account<'info, Escrow>,
+
+    /// Writable so withheld transfer fees can be harvested before closing
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"token_vault", escrow.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = escrow,
+        token::token_program = token_program
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"application",
+            application.recruiter.as_ref(),
+            application.job_hash.as_ref(),
+            application.freelancer.as_ref()
+        ],
+        bump = application.bump,
+        has_one = recruiter,
+        has_one = freelancer,
+        constraint = application.job_hash == escrow.job_hash @ ErrorCode::ApplicationMismatch,
+        constraint = application.mint == Some(mint.key()) @ ErrorCode::InvalidEscrowMint,
+        constraint = !application.rejected @ ErrorCode::ApplicationRejected,
+        close = freelancer
+    )]
+    pub application: Account<'info, Application>,
+
+    #[account(
+        mut,
+        seeds = [b"bond_vault", application.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = application,
+        token::token_program = token_program
+    )]
+    pub bond_vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub recruiter: Signer<'info>,
+
+    /// CHECK: receives the application and bond vault rent; checked by `has_one`
+    #[account(mut)]
+    pub freelancer: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct ReleaseCollateral<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"escrow",
+            escrow.recruiter.as_ref(),
+            escrow.job_hash.as_ref(),
+            &escrow.generation_seed()
+        ],
+        bump = escrow.bump,
+        has_one = freelancer,
+        constraint = escrow.mint.is_none() @ ErrorCode::InvalidEscrowMint
+    )]
+    pub escrow: Account<'info, Escrow>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", escrow.key().as_ref()],
+        bump = escrow.vault_bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    /// CHECK: receives the collateral; checked by `has_one`
+    #[account(mut)]
+    pub freelancer: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ReleaseTokenCollateral<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"escrow",
+            escrow.recruiter.as_ref(),
+            escrow.job_hash.as_ref(),
+            &escrow.generation_seed()
+        ],
+        bump = escrow.bump,
+        has_one = freelancer,
+        constraint = escrow.mint == Some(mint.key()) @ ErrorCode::InvalidEscrowMint
+    )]
+    pub escrow: Account<'info, Escrow>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"token_vault", escrow.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = escrow,
+        token::token_program = token_program
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = freelancer,
+        token::token_program = token_program
+    )]
+    pub freelancer_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: owner of the receiving token account; checked by `has_one`
+    pub freelancer: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct SlashCollateral<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"escrow",
+            escrow.recruiter.as_ref(),
+            escrow.job_hash.as_ref(),
+            &escrow.generation_seed()
+        ],
+        bump = escrow.bump,
+        has_one = recruiter,
+        constraint = escrow.mint.is_none() @ ErrorCode::InvalidEscrowMint
+    )]
+    pub escrow: Account<'info, Escrow>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", escrow.key().as_ref()],
+        bump = escrow.vault_bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(seeds = [b"multisig"], bump = multisig.bump)]
+    pub multisig: Account<'info, Multisig>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"proposal",
+            multisig.key().as_ref(),
+            &proposal.index.to_le_bytes()
+        ],
+        bump = proposal.bump,
+        has_one = multisig,
+        has_one = proposer,
+        close = proposer
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(mut)]
+    pub recruiter: Signer<'info>,
+
+    /// CHECK: receives the proposal rent; checked by `has_one`
+    #[account(mut)]
+    pub proposer: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SlashTokenCollateral<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"escrow",
+            escrow.recruiter.as_ref(),
+            escrow.job_hash.as_ref(),
+            &escrow.generation_seed()
+        ],
+        bump = escrow.bump,
+        has_one = recruiter,
+        constraint = escrow.mint == Some(mint.key()) @ ErrorCode::InvalidEscrowMint
+    )]
+    pub escrow: Account<'info, Escrow>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"token_vault", escrow.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = escrow,
+        token::token_program = token_program
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(seeds = [b"multisig"], bump = multisig.bump)]
+    pub multisig: Account<'info, Multisig>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"proposal",
+            multisig.key().as_ref(),
+            &proposal.index.to_le_bytes()
+        ],
+        bump = proposal.bump,
+        has_one = multisig,
+        has_one = proposer,
+        close = proposer
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = recruiter,
+        token::token_program = token_program
+    )]
+    pub recruiter_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub recruiter: Signer<'info>,
+
+    /// CHECK: receives the proposal rent; checked by `has_one`
+    #[account(mut)]
+    pub proposer: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Escrow state. Declared by hand rather than with `#[account]` so it decodes
+/// both layouts: a version 0 account is upgraded in memory and keeps
+/// `version` 0 until `upgrade_legacy_escrow` rewrites it in place.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct Escrow {
+    pub version: u8,                      // 1 (see ESCROW_VERSION)
+    pub recruiter: Pubkey,                // 32
+    pub freelancer: Pubkey,               // 32
+    pub job_hash: [u8; 32],               // 32 (sha256 of the job id)
+    pub milestone_amounts: Vec<u64>,      // 4 + 8 * n
+    pub milestone_approved_amounts: Vec<u64>, // 4 + 8 * n
+    pub milestone_claimed_amounts: Vec<u64>,  // 4 + 8 * n
+    pub milestones_submitted_at: Vec<i64>, // 4 + 8 * n (0 = not submitted)
+    pub milestone_due_dates: Vec<i64>,    // 4 + 8 * n (0 = no deadline)
+    pub review_period: i64,               // 8 (seconds)
+    pub mint: Option<Pubkey>,             // 1 + 32 (None = native SOL)
+    pub terms: JobTerms,                  // 32 + 8 + 8
+    pub fee_bps: u16,                     // 2 (platform fee locked in at creation)
+    pub generation: Option<u32>,          // 1 + 4 (None = pre-counter v0 escrow)
+    pub collateral: u64,                  // 8 (rolled-in application bond)
+    pub status: EscrowStatus,             // 1
+    pub vault_bump: u8,                   // 1 (SOL vault PDA; unused for tokens)
+    pub bump: u8,                         // 1
+    pub reserved: [u8; 64],               // 64 (zeroed; room for new fields without a realloc)
+}
+
+impl Discriminator for Escrow {
+    // sha256("account:Escrow")[..8], as `#[account]` derives it
+    const DISCRIMINATOR: &'static [u8] = &[31, 213, 123, 187, 186, 22, 218, 155];
+}
+
+impl Owner for Escrow {
+    fn owner() -> Pubkey {
+        crate::ID
+    }
+}
+
+impl AccountSerialize for Escrow {
+    fn try_serialize<W: std::io::Write>(&self, writer: &mut W) -> Result<()> {
+        // A version 0 escrow only fits its old account once upgraded
+        require!(self.version != 0, ErrorCode::EscrowNotMigrated);
+        writer
+            .write_all(Self::DISCRIMINATOR)
+            .map_err(|_| anchor_lang::error::ErrorCode::AccountDidNotSerialize)?;
+        AnchorSerialize::serialize(self, writer)
+            .map_err(|_| anchor_lang::error::ErrorCode::AccountDidNotSerialize)?;
+        Ok(())
+    }
+}
+
+impl AccountDeserialize for Escrow {
+    fn try_deserialize(buf: &mut &[u8]) -> Result<Self> {
+        require!(
+            buf.len() >= 8 && buf[..8] == *Self::DISCRIMINATOR,
+            anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch
+        );
+        Self::try_deserialize_unchecked(buf)
+    }
+
+    fn try_deserialize_unchecked(buf: &mut &[u8]) -> Result<Self> {
+        // Every version 0 escrow has the same fixed size, which no current
+        // layout can have
+        if buf.len() == EscrowV0::SPACE {
+            let legacy = EscrowV0::deserialize(&mut &buf[8..])
+                .map_err(|_| anchor_lang::error::ErrorCode::AccountDidNotDeserialize)?;
+            return Ok(legacy.migrate());
+        }
+        Self::deserialize(&mut &buf[8..])
+            .map_err(|_| error!(anchor_lang::error::ErrorCode::AccountDidNotDeserialize))
+    }
+}
+
+/// Per-job counter PDA. Each escrow for a job is derived from the counter
+/// value at creation, so a recruiter can fund the same job again after a
+/// cancellation; the current escrow is generation `escrow_count - 1`.
+#[account]
+#[derive(InitSpace)]
+pub struct JobCounter {
+    pub escrow_count: u32,
+    pub bump: u8,
+}
+
+impl JobCounter {
+    /// Claims the next generation for a new escrow
+    pub fn next_generation(&mut self) -> Result<u32> {
+        let generation = self.escrow_count;
+        self.escrow_count = generation
+            .checked_add(1)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        Ok(generation)
+    }
+}
+
+/// Original escrow layout (version 0): exactly three milestones with bool
+/// approval and claim flags. Only read by `migrate_escrow`.
+#[derive(AnchorDeserialize)]
+pub struct EscrowV0 {
+    pub recruiter: Pubkey,              // 32
+    pub freelancer: Pubkey,             // 32
+    pub job_id: String,                 // 4 + 50
+    pub milestone_amounts: [u64; 3],    // 8 * 3
+    pub milestones_approved: [bool; 3], // 1 * 3
+    pub milestones_claimed: [bool; 3],  // 1 * 3
+    pub bump: u8,                       // 1
+}
+
+impl EscrowV0 {
+    /// Account size (discriminator included); every version 0 escrow has it
+    pub const SPACE: usize = 8 + 32 + 32 + (4 + 50) + 8 * 3 + 3 + 3 + 1;
+
+    /// Decodes a version 0 escrow. The discriminator is shared with `Escrow`,
+    /// so the fixed size is what tells the two layouts apart.
+    pub fn try_from_account(info: &AccountInfo) -> Result<Self> {
+        let data = info.try_borrow_data()?;
+        require!(
+            data.len() >= 8 && data[..8] == *Escrow::DISCRIMINATOR,
+            anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch
+        );
+        require!(data.len() == Self::SPACE, ErrorCode::EscrowAlreadyMigrated);
+
+        Self::deserialize(&mut &data[8..])
+            .map_err(|_| error!(anchor_lang::error::ErrorCode::AccountDidNotDeserialize))
+    }
+
+    /// Converts to the current layout in memory. Approved milestones become
+    /// fully approved amounts; status is derived from the approval and claim
+    /// flags. `version` stays 0 until the account itself is rewritten.
+    pub fn migrate(&self) -> Escrow {
+        let approved_amounts = self
+            .milestone_amounts
+            .iter()
+            .zip(self.milestones_approved)
+            .map(|(&amount, approved)| if approved { amount } else { 0 })
+            .collect();
+        let claimed_amounts = self
+            .milestone_amounts
+            .iter()
+            .zip(self.milestones_claimed)
+            .map(|(&amount, claimed)| if claimed { amount } else { 0 })
+            .collect();
+
+        let mut escrow = Escrow {
+            version: 0,
+            recruiter: self.recruiter,
+            freelancer: self.freelancer,
+            job_hash: hash_job_id(&self.job_id),
+            milestone_amounts: self.milestone_amounts.to_vec(),
+            milestone_approved_amounts: approved_amounts,
+            milestone_claimed_amounts: claimed_amounts,
+            milestones_submitted_at: vec![0; 3],
+            milestone_due_dates: vec![0; 3],
+            review_period: DEFAULT_REVIEW_PERIOD,
+            mint: None,
+            terms: JobTerms::default(),
+            // Funded before platform fees existed
+            fee_bps: 0,
+            generation: None,
+            collateral: 0,
+            status: EscrowStatus::Funded,
+            vault_bump: 0,
+            bump: self.bump,
+            reserved: [0; 64],
+        };
+        escrow.refresh_status();
+        escrow
+    }
+}
+
+/// Lifecycle of an escrow. Pending becomes Funded once the freelancer accepts.
+/// Funded and InProgress accept milestone activity;
+/// Disputed and Flagged are frozen; Completed, Cancelled and Terminated are
+/// final and only allow closing.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum EscrowStatus {
+    /// Funds locked, waiting for the freelancer to accept the terms
+    Pending,
+    /// Funds locked, no milestone submitted or approved yet
+    Funded,
+    /// At least one milestone submitted or approved
+    InProgress,
+    /// Frozen until the arbitrator resolves the open dispute
+    Disputed,
+    /// Frozen by the platform for review until unflagged or emergency-closed
+    Flagged,
+    /// Every milestone claimed or refunded
+    Completed,
+    /// Recruiter cancelled and was refunded
+    Cancelled,
+    /// Platform authority emergency-closed the escrow
+    Terminated,
+}
+
+impl EscrowStatus {
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            EscrowStatus::Completed | EscrowStatus::Cancelled | EscrowStatus::Terminated
+        )
+    }
+
+    /// Only disputed or flagged escrows may have their funds moved by the
+    /// platform
+    pub fn allows_platform_action(&self) -> bool {
+        matches!(self, EscrowStatus::Disputed | EscrowStatus::Flagged)
+    }
+}
+
+impl Escrow {
+    /// Account size (discriminator included) for an escrow with `milestone_count` milestones
+    pub fn space(milestone_count: usize) -> usize {
+        8 + 1 + 32 + 32 + 32
+            + (4 + 8 * milestone_count) * 5
+            + 8
+            + (1 + 32)
+            + (32 + 8 + 8)
+            + 2
+            + (1 + 4)
+            + 8
+            + 1
+            + 1
+            + 1
+            + 64
+    }
+
+    /// Generation seed of the escrow PDA. Escrows migrated from version 0
+    /// were derived before job counters existed and have none.
+    pub fn generation_seed(&self) -> Vec<u8> {
+        self.generation
+            .map(|generation| generation.to_le_bytes().to_vec())
+            .unwrap_or_default()
+    }
+
+    /// Whether the freelancer accepted the job terms on-chain. Escrows
+    /// migrated from version 0 were funded before acceptance existed.
+    pub fn is_accepted(&self) -> bool {
+        self.status != EscrowStatus::Pending && self.generation.is_some()
+    }
+
+    /// Sets milestone amounts and due dates and resets all per-milestone state
+    pub fn set_milestones(&mut self, milestone_amounts: Vec<u64>, milestone_due_dates: Vec<i64>) {
+        let milestone_count = milestone_amounts.len();
+        self.milestone_amounts = milestone_amounts;
+        self.milestone_approved_amounts = vec![0; milestone_count];
+        self.milestone_claimed_amounts = vec![0; milestone_count];
+        self.milestones_submitted_at = vec![0; milestone_count];
+        self.milestone_due_dates = milestone_due_dates;
+    }
+
+    /// Sum of all milestone amounts, including claimed ones
+    pub fn total_amount(&self) -> Result<u64> {
+        self.milestone_amounts
+            .iter()
+            .try_fold(0u64, |acc, &amount| acc.checked_add(amount))
+            .ok_or_else(|| error!(ErrorCode::ArithmeticOverflow))
+    }
+
+    /// Validates an amended milestone list and returns its total. A milestone
+    /// reclaimed after its due date has already shrunk to 0 and may keep that
+    /// amount; every other milestone must be positive.
+    pub fn amended_total(&self, milestone_amounts: &[u64]) -> Result<u64> {
+        require!(
+            !milestone_amounts.is_empty() && milestone_amounts.len() <= MAX_MILESTONES,
+            ErrorCode::InvalidMilestoneCount
+        );
+
+        milestone_amounts
+            .iter()
+            .enumerate()
+            .try_fold(0u64, |acc, (i, &amount)| {
+                let reclaimed = self.milestone_amounts.get(i) == Some(&0);
+                require!(amount > 0 || reclaimed, ErrorCode::InvalidMilestoneAmount);
+                acc.checked_add(amount)
+                    .ok_or_else(|| error!(ErrorCode::ArithmeticOverflow))
+            })
+    }
+
+    /// Replaces the milestone list with an amended one. Milestones with any
+    /// approval are locked and must keep their index, amount and due date.
+    /// Unchanged unapproved milestones keep their pending submission.
+    pub fn amend_milestones(
+        &mut self,
+        milestone_amounts: Vec<u64>,
+        milestone_due_dates: Vec<i64>,
+    ) -> Result<()> {
+        let new_count = milestone_amounts.len();
+        require!(
+            milestone_due_dates.len() == new_count,
+            ErrorCode::InvalidDueDate
+        );
+
+        for (i, &approved) in self.milestone_approved_amounts.iter().enumerate() {
+            if approved > 0 {
+                require!(
+                    i < new_count
+                        && milestone_amounts[i] == self.milestone_amounts[i]
+                        && milestone_due_dates[i] == self.milestone_due_dates[i],
+                    ErrorCode::ApprovedMilestoneLocked
+                );
+            }
+        }
+
+        let now = Clock::get()?.unix_timestamp;
+        let mut approved_amounts = vec![0; new_count];
+        let mut claimed_amounts = vec![0; new_count];
+        let mut submitted_at = vec![0; new_count];
+        for i in 0..new_count {
+            let existing = i < self.milestone_amounts.len();
+            let due = milestone_due_dates[i];
+            let due_unchanged = existing && due == self.milestone_due_dates[i];
+            require!(
+                due == 0 || due > now || due_unchanged,
+                ErrorCode::InvalidDueDate
+            );
+
+            if existing && milestone_amounts[i] == self.milestone_amounts[i] {
+                approved_amounts[i] = self.milestone_approved_amounts[i];
+                claimed_amounts[i] = self.milestone_claimed_amounts[i];
+                submitted_at[i] = self.milestones_submitted_at[i];
+            }
+        }
+
+        self.milestone_amounts = milestone_amounts;
+        self.milestone_approved_amounts = approved_amounts;
+        self.milestone_claimed_amounts = claimed_amounts;
+        self.milestones_submitted_at = submitted_at;
+        self.milestone_due_dates = milestone_due_dates;
+        Ok(())
+    }
+
+    /// Appends new milestones, leaving existing milestone state untouched
+    pub fn append_milestones(
+        &mut self,
+        milestone_amounts: Vec<u64>,
+        milestone_due_dates: Vec<i64>,
+    ) -> Result<()> {
+        let added = milestone_amounts.len();
+        require!(
+            self.milestone_amounts.len() + added <= MAX_MILESTONES,
+            ErrorCode::InvalidMilestoneCount
+        );
+
+        self.milestone_amounts.extend(milestone_amounts);
+        self.milestone_approved_amounts.extend(vec![0; added]);
+        self.milestone_claimed_amounts.extend(vec![0; added]);
+        self.milestones_submitted_at.extend(vec![0; added]);
+        self.milestone_due_dates.extend(milestone_due_dates);
+        Ok(())
+    }
+
+    /// Amount still held for the freelancer or recruiter (total minus claimed)
+    pub fn outstanding_amount(&self) -> Result<u64> {
+        let claimed = self
+            .milestone_claimed_amounts
+            .iter()
+            .try_fold(0u64, |acc, &amount| acc.checked_add(amount))
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        Ok(self.total_amount()? - claimed)
+    }
+
+    /// Everything the recruiter gets back on cancellation: the outstanding
+    /// milestones, plus the advance while it has not been paid out
+    pub fn refundable_amount(&self) -> Result<u64> {
+        let advance = if self.status == EscrowStatus::Pending {
+            self.terms.advance
+        } else {
+            0
+        };
+        self.outstanding_amount()?
+            .checked_add(advance)
+            .ok_or(ErrorCode::ArithmeticOverflow.into())
+    }
+
+    /// Moves a Pending escrow to Funded if `terms_hash` matches and the
+    /// acceptance deadline has not passed. Returns the advance to pay out.
+    pub fn accept(&mut self, terms_hash: [u8; 32], now: i64) -> Result<u64> {
+        require!(
+            self.status == EscrowStatus::Pending,
+            ErrorCode::EscrowNotPending
+        );
+        require!(
+            now <= self.terms.acceptance_deadline,
+            ErrorCode::AcceptanceDeadlinePassed
+        );
+        require!(
+            terms_hash == self.terms.terms_hash,
+            ErrorCode::TermsHashMismatch
+        );
+        self.status = EscrowStatus::Funded;
+        Ok(self.terms.advance)
+    }
+
+    /// Rejects a refund of an unaccepted escrow before its deadline
+    pub fn require_acceptance_expired(&self, now: i64) -> Result<()> {
+        require!(
+            self.status == EscrowStatus::Pending,
+            ErrorCode::EscrowNotPending
+        );
+        require!(
+            now > self.terms.acceptance_deadline,
+            ErrorCode::AcceptanceDeadlineNotReached
+        );
+        Ok(())
+    }
+
+    /// Cancellation refunds the recruiter in full, so it is only allowed
+    /// while no milestone has been approved or is awaiting review
+    pub fn require_cancellable(&self) -> Result<()> {
+        require!(
+            self.milestone_approved_amounts.iter().all(|&approved| approved == 0),
+            ErrorCode::CannotCancelAfterApproval
+        );
+        require!(
+            self.milestones_submitted_at.iter().all(|&submitted_at| submitted_at == 0),
+            ErrorCode::CannotCancelAfterSubmission
+        );
+        Ok(())
+    }
+
+    /// Rejects milestone activity unless the escrow is Funded or InProgress
+    pub fn require_active(&self) -> Result<()> {
+        match self.status {
+            EscrowStatus::Funded | EscrowStatus::InProgress => Ok(()),
+            EscrowStatus::Pending => err!(ErrorCode::JobNotAccepted),
+            EscrowStatus::Disputed => err!(ErrorCode::EscrowDisputed),
+            EscrowStatus::Flagged => err!(ErrorCode::EscrowFlagged),
+            _ => err!(ErrorCode::EscrowNotActive),
+        }
+    }
+
+    /// Moves an active escrow forward after a milestone change: Funded becomes
+    /// InProgress on the first submission or approval, and either becomes
+    /// Completed once every milestone is settled
+    pub fn refresh_status(&mut self) {
+        if self.is_settled() {
+            self.status = EscrowStatus::Completed;
+        } else if self.status == EscrowStatus::Funded
+            && (self.milestones_submitted_at.iter().any(|&at| at != 0)
+                || self.milestone_approved_amounts.iter().any(|&approved| approved > 0))
+        {
+            self.status = EscrowStatus::InProgress;
+        }
+    }
+
+    /// Applies an arbitrator's ruling: each milestone shrinks to what was
+    /// already claimed plus the freelancer's award, which counts as claimed
+    pub fn apply_dispute_ruling(&mut self, freelancer_amounts: &[u64]) {
+        for (i, &award) in freelancer_amounts.iter().enumerate() {
+            let settled = self.milestone_claimed_amounts[i] + award;
+            self.milestone_amounts[i] = settled;
+            self.milestone_approved_amounts[i] = settled;
+            self.milestone_claimed_amounts[i] = settled;
+            self.milestones_submitted_at[i] = 0;
+        }
+        self.status = EscrowStatus::Completed;
+    }
+
+    /// Empties the collateral ledger, returning the amount to pay out
+    pub fn take_collateral(&mut self) -> Result<u64> {
+        require!(self.collateral > 0, ErrorCode::NoCollateral);
+        Ok(std::mem::take(&mut self.collateral))
+    }
+
+    /// Pays out every approved-but-unclaimed amount and refunds the rest:
+    /// each milestone shrinks to its approved amount, all of which counts as
+    /// claimed. Returns the total owed to the freelancer.
+    pub fn settle_approved(&mut self) -> Result<u64> {
+        let mut owed: u64 = 0;
+        for i in 0..self.milestone_amounts.len() {
+            let approved = self.milestone_approved_amounts[i];
+            owed = owed
+                .checked_add(approved - self.milestone_claimed_amounts[i])
+                .ok_or(ErrorCode::ArithmeticOverflow)?;
+            self.milestone_amounts[i] = approved;
+            self.milestone_claimed_amounts[i] = approved;
+            self.milestones_submitted_at[i] = 0;
+        }
+        Ok(owed)
+    }
+
+    /// True once every milestone is fully claimed or refunded
+    pub fn is_settled(&self) -> bool {
+        self.milestone_amounts
+            .iter()
+            .zip(&self.milestone_claimed_amounts)
+            .all(|(&amount, &claimed)| amount == claimed)
+    }
+
+    pub fn is_fully_approved(&self, index: usize) -> bool {
+        self.milestone_approved_amounts[index] == self.milestone_amounts[index]
+    }
+
+    /// Marks the approved, unclaimed part of a milestone as claimed and returns it
+    pub fn take_claimable(&mut self, index: usize) -> Result<u64> {
+        let approved = self.milestone_approved_amounts[index];
+        require!(approved > 0, ErrorCode::MilestoneNotApproved);
+
+        let claimable = approved - self.milestone_claimed_amounts[index];
+        require!(claimable > 0, ErrorCode::MilestoneAlreadyClaimed);
+
+        self.milestone_claimed_amounts[index] = approved;
+        Ok(claimable)
+    }
+
+    /// Shrinks a partially approved milestone to its approved amount and
+    /// returns the remainder owed back to the recruiter
+    pub fn release_unapproved_remainder(&mut self, index: usize) -> Result<u64> {
+        let approved = self.milestone_approved_amounts[index];
+        require!(
+            approved > 0 && approved < self.milestone_amounts[index],
+            ErrorCode::MilestoneNotPartiallyApproved
+        );
+
+        let refund = self.milestone_amounts[index] - approved;
+        self.milestone_amounts[index] = approved;
+        self.milestones_submitted_at[index] = 0;
+        Ok(refund)
+    }
+
+    /// Shrinks every milestone that is past due and unsubmitted to its approved
+    /// amount, returning the total owed back to the recruiter
+    pub fn release_expired_milestones(&mut self, now: i64) -> Result<u64> {
+        let mut refund: u64 = 0;
+        for i in 0..self.milestone_amounts.len() {
+            let due = self.milestone_due_dates[i];
+            let expired = due != 0
+                && now > due
+                && self.milestones_submitted_at[i] == 0
+                && !self.is_fully_approved(i);
+            if expired {
+                let remainder = self.milestone_amounts[i] - self.milestone_approved_amounts[i];
+                refund = refund
+                    .checked_add(remainder)
+                    .ok_or(ErrorCode::ArithmeticOverflow)?;
+                self.milestone_amounts[i] = self.milestone_approved_amounts[i];
+            }
+        }
+
+        require!(refund > 0, ErrorCode::NothingToReclaim);
+        Ok(refund)
+    }
+
+    /// Validates a client-supplied milestone index against this escrow
+    pub fn milestone_index(&self, milestone_index: u8) -> Result<usize> {
+        let index = milestone_index as usize;
+        require!(
+            index < self.milestone_amounts.len(),
+            ErrorCode::InvalidMilestoneIndex
+        );
+        Ok(index)
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Config {
+    pub authority: Pubkey,              // 32
+    pub pending_authority: Option<Pubkey>, // 1 + 32 (awaiting acceptance)
+    pub treasury: Pubkey,               // 32
+    pub fee_bps: u16,                   // 2
+    pub arbitrator: Pubkey,             // 32
+    pub dispute_bond: u64,              // 8
+    pub application_bond: u64,          // 8 (minimum SOL bond to apply)
+    pub paused: PauseFlags,             // 4
+    #[max_len(MAX_BOND_MINTS)]
+    pub bond_mints: Vec<BondMint>,      // 4 + 40 * MAX_BOND_MINTS
+    pub bump: u8,                       // 1
+}
+
+impl Config {
+    /// Minimum application bond for `mint`, or `None` if the mint is not
+    /// accepted for token bonds
+    pub fn min_bond_for(&self, mint: &Pubkey) -> Option<u64> {
+        self.bond_mints
+            .iter()
+            .find(|bond_mint| bond_mint.mint == *mint)
+            .map(|bond_mint| bond_mint.min_bond)
+    }
+}
+
+/// A mint accepted for token application bonds and its minimum bond
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug, InitSpace)]
+pub struct BondMint {
+    pub mint: Pubkey,
+    pub min_bond: u64,
+}
+
+/// Terms the freelancer signs with `accept_job` before an escrow is binding
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default, PartialEq, Eq, Debug)]
+pub struct JobTerms {
+    /// Hash of the off-chain job terms
+    pub terms_hash: [u8; 32],
+    /// Paid to the freelancer on acceptance, on top of the milestones
+    pub advance: u64,
+    /// Unix time after which anyone can refund an unaccepted escrow
+    pub acceptance_deadline: i64,
+}
+
+impl JobTerms {
+    /// Rejects terms whose acceptance deadline has already passed
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.acceptance_deadline > Clock::get()?.unix_timestamp,
+            ErrorCode::InvalidAcceptanceDeadline
+        );
+        Ok(())
+    }
+}
+
+/// Instruction classes the platform can pause independently
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default, PartialEq, Eq, Debug, InitSpace)]
+pub struct PauseFlags {
+    /// `create_job_escrow` / `create_token_job_escrow`
+    pub creates: bool,
+    /// `claim_milestone` / `accept_job` and their token variants
+    pub claims: bool,
+    /// Recruiter outflows: `cancel_job`, `refund_unaccepted_job`,
+    /// `refund_milestone_remainder`, `reclaim_expired_milestones` and their
+    /// token variants
+    pub cancellations: bool,
+    /// Top-ups and rewrites: `amend_escrow`, `add_milestones` and their
+    /// token variants
+    pub amendments: bool,
+}
+
+/// Fee ledger PDA. SOL fees are held in this account's lamports; token fees
+/// in its associated token account for each mint.
+#[account]
+#[derive(InitSpace)]
+pub struct Treasury {
+    pub accrued_lamports: u64,          // 8 (collected, not yet withdrawn)
+    pub total_lamports_collected: u64,  // 8 (lifetime)
+    pub bump: u8,                       // 1
+}
+
+impl Treasury {
+    /// Records a SOL fee that has been moved into this account
+    pub fn credit_lamports(&mut self, fee: u64) -> Result<()> {
+        self.accrued_lamports = self
+            .accrued_lamports
+            .checked_add(fee)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        self.total_lamports_collected = self
+            .total_lamports_collected
+            .checked_add(fee)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        Ok(())
+    }
+}
+
+/// Platform multisig: an action needs `threshold` approvals from `owners`
+#[account]
+#[derive(InitSpace)]
+pub struct Multisig {
+    #[max_len(MAX_MULTISIG_OWNERS)]
+    pub owners: Vec<Pubkey>,            // 4 + 32 * MAX_MULTISIG_OWNERS
+    pub threshold: u8,                  // 1
+    pub timelock_delay: i64,            // 8 (seconds between approval and execution)
+    pub owner_set_seqno: u32,           // 4 (bumped on every owner change)
+    pub proposal_count: u64,            // 8 (next proposal index)
+    pub bump: u8,                       // 1
+}
+
+impl Multisig {
+    /// Position of `key` in the owner set
+    pub fn owner_index(&self, key: &Pubkey) -> Result<usize> {
+        self.owners
+            .iter()
+            .position(|owner| owner == key)
+            .ok_or_else(|| error!(ErrorCode::NotMultisigOwner))
+    }
+
+    /// Checks that a proposal was made under the current owner set, has
+    /// reached the approval threshold and has sat out its notice period
+    pub fn require_approved(&self, proposal: &Proposal) -> Result<()> {
+        require!(
+            proposal.owner_set_seqno == self.owner_set_seqno,
+            ErrorCode::StaleProposal
+        );
+        require!(
+            proposal.approval_count() >= self.threshold && proposal.executable_at != 0,
+            ErrorCode::ProposalNotApproved
+        );
+        require!(
+            Clock::get()?.unix_timestamp >= proposal.executable_at,
+            ErrorCode::TimelockNotExpired
+        );
+        Ok(())
+    }
+}
+
+/// A platform action awaiting multisig approval
+#[account]
+#[derive(InitSpace)]
+pub struct Proposal {
+    pub multisig: Pubkey,               // 32
+    pub index: u64,                     // 8
+    pub proposer: Pubkey,               // 32
+    pub action: PlatformAction,         // 1 + largest variant
+    #[max_len(MAX_MULTISIG_OWNERS)]
+    pub approvals: Vec<bool>,           // 4 + MAX_MULTISIG_OWNERS (one per owner)
+    pub owner_set_seqno: u32,           // 4
+    pub created_at: i64,                // 8
+    pub executable_at: i64,             // 8 (0 = threshold not reached yet)
+    pub bump: u8,                       // 1
+}
+
+impl Proposal {
+    pub fn approval_count(&self) -> u8 {
+        self.approvals.iter().filter(|&&approved| approved).count() as u8
+    }
+
+    /// Starts the notice period once the threshold is first reached.
+    /// Actions that move or redirect funds unlock after the multisig's
+    /// timelock; owner changes are executable immediately.
+    pub fn queue_if_approved(&mut self, key: Pubkey, multisig: &Multisig) -> Result<()> {
+        if self.executable_at != 0 || self.approval_count() < multisig.threshold {
+            return Ok(());
+        }
+
+        let now = Clock::get()?.unix_timestamp;
+        let delay = match self.action {
+            PlatformAction::EmergencyClose { .. }
+            | PlatformAction::SlashCollateral { .. }
+            | PlatformAction::WithdrawFees { .. }
+            | PlatformAction::SetFees { .. } => multisig.timelock_delay,
+            PlatformAction::SetOwners { .. } | PlatformAction::FlagEscrow { .. } => 0,
+        };
+        // Never 0, which marks an unqueued proposal
+        self.executable_at = now
+            .checked_add(delay)
+            .ok_or(ErrorCode::ArithmeticOverflow)?
+            .max(1);
+
+        emit!(PlatformActionQueued {
+            proposal: key,
+            action: self.action.clone(),
+            executable_at: self.executable_at,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug, InitSpace)]
+pub enum PlatformAction {
+    /// Terminate an escrow, returning its funds to the freelancer and recruiter
+    EmergencyClose { escrow: Pubkey },
+    /// Forfeit a freelancer's collateral to the recruiter
+    SlashCollateral { escrow: Pubkey },
+    /// Move accrued fees (SOL when `mint` is None) to the treasury wallet
+    WithdrawFees { mint: Option<Pubkey>, amount: u64 },
+    /// Change the fee charged on new escrows and the wallet fees go to
+    SetFees { treasury: Pubkey, fee_bps: u16 },
+    /// Replace the multisig owner set and threshold
+    SetOwners {
+        #[max_len(MAX_MULTISIG_OWNERS)]
+        owners: Vec<Pubkey>,
+        threshold: u8,
+    },
+    /// Freeze an active escrow so it becomes eligible for platform action
+    FlagEscrow { escrow: Pubkey },
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Dispute {
+    pub escrow: Pubkey,                 // 32
+    pub opened_by: Pubkey,              // 32
+    pub arbitrator: Pubkey,             // 32
+    pub bond: u64,                      // 8
+    pub reason_hash: [u8; 32],          // 32 (hash of off-chain evidence)
+    pub opened_at: i64,                 // 8
+    pub bump: u8,                       // 1
+}
+
+/// A freelancer's bonded application to a job. SOL bonds are held in this
+/// account's lamports, token bonds in its `bond_vault` token account.
+#[account]
+#[derive(InitSpace)]
+pub struct Application {
+    pub recruiter: Pubkey,              // 32
+    pub freelancer: Pubkey,             // 32
+    pub job_hash: [u8; 32],             // 32
+    pub mint: Option<Pubkey>,           // 1 + 32 (None = SOL bond)
+    pub bond: u64,                      // 8
+    pub applied_at: i64,                // 8
+    pub rejected: bool,                 // 1
+    pub bump: u8,                       // 1
+}
+
+impl Application {
+    /// A bond is returned once the recruiter rejects the applicant, or once
+    /// the lock period has passed without a decision
+    pub fn require_reclaimable(&self, now: i64) -> Result<()> {
+        let unlocks_at = self
+            .applied_at
+            .checked_add(APPLICATION_BOND_LOCK)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        require!(
+            self.rejected || now >= unlocks_at,
+            ErrorCode::ApplicationBondLocked
+        );
+        Ok(())
+    }
+}
+
+#[event]
+pub struct EscrowCreated {
+    pub escrow: Pubkey,
+    pub job_id: String,
+    pub job_hash: [u8; 32],
+    pub recruiter: Pubkey,
+    pub freelancer: Pubkey,
+    pub mint: Option<Pubkey>,
+    pub milestone_amounts: Vec<u64>,
+    pub total_amount: u64,
+    pub terms: JobTerms,
+    pub generation: Option<u32>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct JobAccepted {
+    pub escrow: Pubkey,
+    pub job_hash: [u8; 32],
+    pub freelancer: Pubkey,
+    pub terms_hash: [u8; 32],
+    pub advance: u64,
+    pub fee: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MilestoneSubmitted {
+    pub escrow: Pubkey,
+    pub job_hash: [u8; 32],
+    pub milestone_index: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MilestoneRejected {
+    pub escrow: Pubkey,
+    pub job_hash: [u8; 32],
+    pub milestone_index: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MilestoneApproved {
+    pub escrow: Pubkey,
+    pub job_hash: [u8; 32],
+    pub milestone_index: u8,
+    pub approved_amount: u64,
+    pub milestone_amount: u64,
+    pub auto_approved: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MilestoneClaimed {
+    pub escrow: Pubkey,
+    pub job_hash: [u8; 32],
+    pub milestone_index: u8,
+    pub amount: u64,
+    pub fee: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MilestoneRemainderRefunded {
+    pub escrow: Pubkey,
+    pub job_hash: [u8; 32],
+    pub milestone_index: u8,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ExpiredMilestonesReclaimed {
+    pub escrow: Pubkey,
+    pub job_hash: [u8; 32],
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct EscrowAmended {
+    pub escrow: Pubkey,
+    pub job_hash: [u8; 32],
+    pub milestone_amounts: Vec<u64>,
+    pub previous_total: u64,
+    pub new_total: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MilestonesAdded {
+    pub escrow: Pubkey,
+    pub job_hash: [u8; 32],
+    pub milestone_amounts: Vec<u64>,
+    pub added_amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct JobCancelled {
+    pub escrow: Pubkey,
+    pub job_hash: [u8; 32],
+    pub refund_amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct EscrowClosed {
+    pub escrow: Pubkey,
+    pub job_hash: [u8; 32],
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct DisputeOpened {
+    pub escrow: Pubkey,
+    pub job_hash: [u8; 32],
+    pub opened_by: Pubkey,
+    pub arbitrator: Pubkey,
+    pub bond: u64,
+    pub reason_hash: [u8; 32],
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct DisputeResolved {
+    pub escrow: Pubkey,
+    pub job_hash: [u8; 32],
+    pub arbitrator: Pubkey,
+    pub freelancer_amounts: Vec<u64>,
+    pub freelancer_amount: u64,
+    pub recruiter_amount: u64,
+    pub bond_refunded: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FeeCollected {
+    pub escrow: Pubkey,
+    pub job_hash: [u8; 32],
+    pub mint: Option<Pubkey>,
+    pub milestone_index: u8,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FeesWithdrawn {
+    pub mint: Option<Pubkey>,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct EmergencyClose {
+    pub escrow: Pubkey,
+    pub job_hash: [u8; 32],
+    pub proposal: Pubkey,
+    pub freelancer_amount: u64,
+    pub recruiter_amount: u64,
+    pub fee: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct EscrowMigrated {
+    pub escrow: Pubkey,
+    pub job_hash: [u8; 32],
+    pub from_version: u8,
+    pub to_version: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MultisigOwnersChanged {
+    pub owners: Vec<Pubkey>,
+    pub threshold: u8,
+    pub owner_set_seqno: u32,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ProposalCreated {
+    pub proposal: Pubkey,
+    pub index: u64,
+    pub proposer: Pubkey,
+    pub action: PlatformAction,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ProposalApproved {
+    pub proposal: Pubkey,
+    pub owner: Pubkey,
+    pub approvals: u8,
+    pub threshold: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AuthorityTransferProposed {
+    pub authority: Pubkey,
+    pub pending_authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AuthorityTransferred {
+    pub previous_authority: Pubkey,
+    pub new_authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct EscrowFlagged {
+    pub escrow: Pubkey,
+    pub job_hash: [u8; 32],
+    pub flagged: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PauseFlagsUpdated {
+    pub authority: Pubkey,
+    pub paused: PauseFlags,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BondMintUpdated {
+    pub authority: Pubkey,
+    pub mint: Pubkey,
+    pub min_bond: u64, // 0 = removed
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PlatformActionQueued {
+    pub proposal: Pubkey,
+    pub action: PlatformAction,
+    pub executable_at: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ProposalCancelled {
+    pub proposal: Pubkey,
+    pub cancelled_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ApplicationSubmitted {
+    pub application: Pubkey,
+    pub job_hash: [u8; 32],
+    pub recruiter: Pubkey,
+    pub freelancer: Pubkey,
+    pub mint: Option<Pubkey>,
+    pub bond: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ApplicationRejected {
+    pub application: Pubkey,
+    pub job_hash: [u8; 32],
+    pub freelancer: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ApplicationBondReclaimed {
+    pub application: Pubkey,
+    pub job_hash: [u8; 32],
+    pub freelancer: Pubkey,
+    pub bond: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CollateralPosted {
+    pub escrow: Pubkey,
+    pub job_hash: [u8; 32],
+    pub application: Pubkey,
+    pub amount: u64,
+    pub collateral: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CollateralSettled {
+    pub escrow: Pubkey,
+    pub job_hash: [u8; 32],
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub slashed: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ConfigUpdated {
+    pub authority: Pubkey,
+    pub treasury: Pubkey,
+    pub fee_bps: u16,
+    pub arbitrator: Pubkey,
+    pub dispute_bond: u64,
+    pub application_bond: u64,
+    pub timestamp: i64,
 }
 
 #[error_code]
 pub enum ErrorCode {
-    #[msg("Job ID cannot exceed 50 characters")]
-    JobIdTooLong,
     #[msg("All milestone amounts must be greater than 0")]
     InvalidMilestoneAmount,
-    #[msg("Invalid milestone index (must be 0, 1, or 2)")]
+    #[msg("Invalid milestone index for this escrow")]
     InvalidMilestoneIndex,
     #[msg("Milestone has already been approved")]
     MilestoneAlreadyApproved,
@@ -342,4 +6060,112 @@ pub enum ErrorCode {
     InsufficientEscrowBalance,
     #[msg("Unauthorized: Only platform authority can perform this action")]
     UnauthorizedPlatformAccess,
+    #[msg("Escrow must have between 1 and 20 milestones")]
+    InvalidMilestoneCount,
+    #[msg("Arithmetic overflow")]
+    ArithmeticOverflow,
+    #[msg("Escrow mint does not match this instruction")]
+    InvalidEscrowMint,
+    #[msg("Platform fee cannot exceed MAX_FEE_BPS (10%)")]
+    InvalidFeeBps,
+    #[msg("Escrow is under dispute")]
+    EscrowDisputed,
+    #[msg("Only the recruiter or freelancer of this escrow can do this")]
+    NotEscrowParty,
+    #[msg("Unauthorized: Only the assigned arbitrator can resolve this dispute")]
+    UnauthorizedArbitrator,
+    #[msg("Dispute ruling must award at most the outstanding amount of each milestone")]
+    InvalidDisputeRuling,
+    #[msg("Review period must be greater than 0 seconds")]
+    InvalidReviewPeriod,
+    #[msg("Milestone has already been submitted for review")]
+    MilestoneAlreadySubmitted,
+    #[msg("Milestone has not been submitted for review")]
+    MilestoneNotSubmitted,
+    #[msg("Review window has not elapsed yet")]
+    ReviewWindowOpen,
+    #[msg("Provide one due date per milestone, each 0 or in the future")]
+    InvalidDueDate,
+    #[msg("No milestones are past due without a submission")]
+    NothingToReclaim,
+    #[msg("Milestone has been refunded to the recruiter")]
+    MilestoneRefunded,
+    #[msg("Approved amount must exceed the current approval and not exceed the milestone amount")]
+    InvalidApprovalAmount,
+    #[msg("Milestone must be partially approved to refund its remainder")]
+    MilestoneNotPartiallyApproved,
+    #[msg("Milestones with an approval cannot be amended")]
+    ApprovedMilestoneLocked,
+    #[msg("Escrow still has unclaimed milestones")]
+    EscrowNotSettled,
+    #[msg("Escrow is no longer active")]
+    EscrowNotActive,
+    #[msg("Escrow is not under dispute")]
+    EscrowNotDisputed,
+    #[msg("Escrow already uses the current account layout")]
+    EscrowAlreadyMigrated,
+    #[msg("Multisig needs 1-10 distinct owners and a threshold between 1 and the owner count")]
+    InvalidMultisigOwners,
+    #[msg("Signer is not a multisig owner")]
+    NotMultisigOwner,
+    #[msg("Owner has already approved this proposal")]
+    ProposalAlreadyApproved,
+    #[msg("Proposal has not reached the approval threshold")]
+    ProposalNotApproved,
+    #[msg("Proposal was made under a previous multisig owner set")]
+    StaleProposal,
+    #[msg("Proposal action does not match this instruction or its accounts")]
+    ProposalActionMismatch,
+    #[msg("Signer is not the pending platform authority")]
+    NotPendingAuthority,
+    #[msg("This instruction is paused by the platform")]
+    PlatformPaused,
+    #[msg("Timelock delay cannot be negative")]
+    InvalidTimelockDelay,
+    #[msg("Proposal is still in its notice period")]
+    TimelockNotExpired,
+    #[msg("Amount exceeds the fees accrued in the treasury")]
+    InsufficientAccruedFees,
+    #[msg("Escrow is flagged for platform review")]
+    EscrowFlagged,
+    #[msg("Escrow is not flagged")]
+    EscrowNotFlagged,
+    #[msg("Platform can only act on disputed or flagged escrows")]
+    PlatformActionNotAllowed,
+    #[msg("Freelancer has not accepted this job yet")]
+    JobNotAccepted,
+    #[msg("Escrow is not awaiting acceptance")]
+    EscrowNotPending,
+    #[msg("Terms hash does not match the escrow")]
+    TermsHashMismatch,
+    #[msg("Acceptance deadline must be in the future")]
+    InvalidAcceptanceDeadline,
+    #[msg("Acceptance deadline has passed")]
+    AcceptanceDeadlinePassed,
+    #[msg("Acceptance deadline has not passed yet")]
+    AcceptanceDeadlineNotReached,
+    #[msg("Bond is below the platform's minimum application bond")]
+    InsufficientApplicationBond,
+    #[msg("Application bond is locked until rejection or the lock period ends")]
+    ApplicationBondLocked,
+    #[msg("Application is for a different job")]
+    ApplicationMismatch,
+    #[msg("Escrow holds no collateral")]
+    NoCollateral,
+    #[msg("Collateral must be released or slashed first")]
+    CollateralNotSettled,
+    #[msg("Cannot cancel job while a milestone is awaiting review")]
+    CannotCancelAfterSubmission,
+    #[msg("Cancelling an accepted job needs the freelancer's signature")]
+    FreelancerConsentRequired,
+    #[msg("A disputed escrow must be closed together with its dispute")]
+    DisputeAccountRequired,
+    #[msg("Mint is not accepted for application bonds")]
+    BondMintNotAllowed,
+    #[msg("Too many application bond mints")]
+    TooManyBondMints,
+    #[msg("Application was rejected")]
+    ApplicationRejected,
+    #[msg("Escrow is still in the version 0 layout; call migrate_escrow first (only approve, claim and cancel upgrade it themselves)")]
+    EscrowNotMigrated,
 }
\ No newline at end of file
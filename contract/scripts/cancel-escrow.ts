@@ -1,5 +1,5 @@
 import * as anchor from "@coral-xyz/anchor";
-import { Program } from "@coral-xyz/anchor";
+import { BN, Program } from "@coral-xyz/anchor";
 import { FreelancePlatform } from "../target/types/freelance_platform";
 import { PublicKey } from "@solana/web3.js";
 import { createHash } from "crypto";
@@ -18,12 +18,26 @@ async function main() {
     // Your job ID
     const jobId = "24d0baa5-e36a-4f8c-9db2-704774e6b6c6";
 
-    // Derive escrow PDA
+    // The current escrow is the job counter's latest generation. Jobs funded
+    // before counters existed keep their original address (no generation seed).
+    const [jobCounterPDA] = PublicKey.findProgramAddressSync(
+        [
+            Buffer.from("job"),
+            provider.wallet.publicKey.toBuffer(),
+            hashJobId(jobId),
+        ],
+        program.programId
+    );
+    const jobCounter = await program.account.jobCounter.fetchNullable(jobCounterPDA);
+    const generationSeed = jobCounter
+        ? [new BN(jobCounter.escrowCount - 1).toArrayLike(Buffer, "le", 4)]
+        : [];
     const [escrowPDA] = PublicKey.findProgramAddressSync(
         [
             Buffer.from("escrow"),
             provider.wallet.publicKey.toBuffer(),
             hashJobId(jobId),
+            ...generationSeed,
         ],
         program.programId
     );
@@ -32,12 +46,25 @@ async function main() {
     console.log("Recruiter:", provider.wallet.publicKey.toBase58());
 
     // Check if escrow exists
-    const escrowAccount = await connection.getAccountInfo(escrowPDA);
-    if (!escrowAccount) {
+    const escrow = await program.account.escrow.fetchNullable(escrowPDA);
+    if (!escrow) {
         console.log("❌ Escrow does not exist!");
         return;
     }
 
+    if (!("pending" in escrow.status || "funded" in escrow.status || "inProgress" in escrow.status)) {
+        console.log("❌ Escrow is no longer active:", Object.keys(escrow.status)[0]);
+        return;
+    }
+
+    // Once the freelancer has accepted, cancelling needs their signature too.
+    // Jobs funded before acceptance existed (no generation) never were.
+    if (escrow.generation !== null && !("pending" in escrow.status)) {
+        console.log("❌ The freelancer has accepted this job.");
+        console.log("Cancelling now needs their signature, or open a dispute instead.");
+        return;
+    }
+
     console.log("✅ Escrow found, attempting to cancel...");
 
     try {
@@ -46,6 +73,7 @@ async function main() {
             .accounts({
                 escrow: escrowPDA,
                 recruiter: provider.wallet.publicKey,
+                freelancer: null,
             })
             .rpc();
 
@@ -62,9 +90,6 @@ async function main() {
     }
 }
 
-// Import connection
-const connection = anchor.getProvider().connection;
-
 main()
     .then(() => process.exit(0))
     .catch((error) => {
@@ -1,7 +1,26 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program, BN } from "@coral-xyz/anchor";
 import { FreelancePlatform } from "../target/types/freelance_platform";
-import { PublicKey, Keypair, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import {
+  PublicKey,
+  Keypair,
+  LAMPORTS_PER_SOL,
+  SystemProgram,
+  Transaction,
+  sendAndConfirmTransaction,
+} from "@solana/web3.js";
+import {
+  ExtensionType,
+  TOKEN_2022_PROGRAM_ID,
+  createInitializeMintInstruction,
+  createInitializeTransferFeeConfigInstruction,
+  createMint,
+  getAccount,
+  getAssociatedTokenAddressSync,
+  getMintLen,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+} from "@solana/spl-token";
 import { assert } from "chai";
 import { createHash } from "crypto";
 
@@ -17,8 +36,9 @@ describe("freelance_platform", () => {
   const program = anchor.workspace
     .FreelancePlatform as Program<FreelancePlatform>;
 
-  let recruiter: Keypair;
-  let freelancer: Keypair;
+  // Created up front so describe blocks can derive PDAs from them
+  const recruiter = Keypair.generate();
+  const freelancer = Keypair.generate();
   let escrowPDA: PublicKey;
   const jobId = "test-job-123";
   const milestoneAmounts = [
@@ -26,12 +46,173 @@ describe("freelance_platform", () => {
     new BN(1.5 * LAMPORTS_PER_SOL),
     new BN(2 * LAMPORTS_PER_SOL),
   ];
+  const reviewPeriod = new BN(2);
+  const noDueDates = (amounts: BN[]) => amounts.map(() => new BN(0));
+  // Escrows are derived from their job counter's value at creation
+  const generationSeed = (generation: number) =>
+    new BN(generation).toArrayLike(Buffer, "le", 4);
+  const jobCounterPDA = (id: string) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("job"), recruiter.publicKey.toBuffer(), hashJobId(id)],
+      program.programId
+    )[0];
+  // Escrowed SOL lives in a system-owned vault next to each escrow
+  const vaultPDA = (escrow: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("vault"), escrow.toBuffer()],
+      program.programId
+    )[0];
+  const [configPDA] = PublicKey.findProgramAddressSync(
+    [Buffer.from("config")],
+    program.programId
+  );
+  const treasury = Keypair.generate().publicKey;
+  const [feeTreasuryPDA] = PublicKey.findProgramAddressSync(
+    [Buffer.from("treasury")],
+    program.programId
+  );
+  const feeBps = 250;
+  const platformFee = (amount: BN) => amount.muln(feeBps).divn(10_000);
+  const arbitrator = Keypair.generate();
+  const disputeBond = new BN(0.1 * LAMPORTS_PER_SOL);
+  const applicationBond = new BN(0.05 * LAMPORTS_PER_SOL);
+  const [programData] = PublicKey.findProgramAddressSync(
+    [program.programId.toBuffer()],
+    new PublicKey("BPFLoaderUpgradeab1e11111111111111111111111")
+  );
 
-  before(async () => {
-    // Create test wallets
-    recruiter = Keypair.generate();
-    freelancer = Keypair.generate();
+  // Decode the program events logged by a confirmed transaction
+  const emittedEvents = async (signature: string) => {
+    const tx = await provider.connection.getTransaction(signature, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    });
+    const parser = new anchor.EventParser(program.programId, program.coder);
+    return Array.from(parser.parseLogs(tx.meta.logMessages));
+  };
+
+  // Terms every test escrow is created with; the freelancer signs the hash
+  const termsHash = Array.from(hashJobId("job-terms"));
+  const jobTerms = (advance = new BN(0), acceptWithin = 3600) => ({
+    termsHash,
+    advance,
+    acceptanceDeadline: new BN(Math.floor(Date.now() / 1000) + acceptWithin),
+  });
+  const acceptJob = (escrow: PublicKey) =>
+    program.methods
+      .acceptJob(termsHash)
+      .accounts({
+        escrow,
+        config: configPDA,
+        feeTreasury: feeTreasuryPDA,
+        freelancer: freelancer.publicKey,
+      })
+      .signers([freelancer])
+      .rpc();
+
+  // Freelancers apply to a job by bonding SOL into an application PDA
+  const applicationPDA = (id: string, applicant: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("application"),
+        recruiter.publicKey.toBuffer(),
+        hashJobId(id),
+        applicant.toBuffer(),
+      ],
+      program.programId
+    )[0];
+  const applyToJob = (id: string, applicant: Keypair, bond = applicationBond) =>
+    program.methods
+      .applyToJob(id, recruiter.publicKey, bond)
+      .accounts({
+        application: applicationPDA(id, applicant.publicKey),
+        config: configPDA,
+        freelancer: applicant.publicKey,
+      })
+      .signers([applicant])
+      .rpc();
+  const rollApplicationBond = (id: string, escrow: PublicKey) =>
+    program.methods
+      .rollApplicationBond()
+      .accounts({
+        escrow,
+        application: applicationPDA(id, freelancer.publicKey),
+        recruiter: recruiter.publicKey,
+        freelancer: freelancer.publicKey,
+      })
+      .signers([recruiter])
+      .rpc();
+
+  // Platform actions need a 2-of-3 multisig, set up in "platform multisig"
+  const owners = [Keypair.generate(), Keypair.generate(), Keypair.generate()];
+  const [multisigPDA] = PublicKey.findProgramAddressSync(
+    [Buffer.from("multisig")],
+    program.programId
+  );
+  const proposalPDA = (index: number) =>
+    PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        multisigPDA.toBuffer(),
+        new BN(index).toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    )[0];
+  const timelockDelay = new BN(2);
+
+  const nextProposalPDA = async () => {
+    const { proposalCount } = await program.account.multisig.fetch(
+      multisigPDA
+    );
+    return proposalPDA(proposalCount.toNumber());
+  };
+
+  // Proposes an action, adds a second approval and waits out the notice period
+  const passProposal = async (
+    action: Parameters<typeof program.methods.proposePlatformAction>[0],
+    escrow: PublicKey | null = null
+  ) => {
+    const proposal = await nextProposalPDA();
+    await program.methods
+      .proposePlatformAction(action)
+      .accounts({
+        multisig: multisigPDA,
+        proposal,
+        escrow,
+        proposer: owners[0].publicKey,
+      })
+      .signers([owners[0]])
+      .rpc();
+    await program.methods
+      .approveProposal()
+      .accounts({
+        multisig: multisigPDA,
+        proposal,
+        owner: owners[1].publicKey,
+      })
+      .signers([owners[1]])
+      .rpc();
+    await new Promise((resolve) =>
+      setTimeout(resolve, (timelockDelay.toNumber() + 1) * 1000)
+    );
+    return proposal;
+  };
+
+  // Freezes an escrow through an approved FlagEscrow proposal
+  const flagEscrow = async (escrow: PublicKey) => {
+    const proposal = await passProposal({ flagEscrow: { escrow } }, escrow);
+    await program.methods
+      .flagEscrow()
+      .accounts({
+        escrow,
+        multisig: multisigPDA,
+        proposal,
+        proposer: owners[0].publicKey,
+      })
+      .rpc();
+  };
 
+  before(async () => {
     // Airdrop SOL to recruiter
     const airdropSig = await provider.connection.requestAirdrop(
       recruiter.publicKey,
@@ -53,20 +234,89 @@ describe("freelance_platform", () => {
         Buffer.from("escrow"),
         recruiter.publicKey.toBuffer(),
         hashJobId(jobId),
+        generationSeed(0),
       ],
       program.programId
     );
   });
 
+  // Config must exist before escrows can be created, claimed or cancelled
+  it("Prevents non-upgrade-authority from initializing config", async () => {
+    try {
+      await program.methods
+        .initializeConfig(
+          recruiter.publicKey,
+          treasury,
+          feeBps,
+          arbitrator.publicKey,
+          disputeBond,
+          applicationBond
+        )
+        .accounts({
+          config: configPDA,
+          program: program.programId,
+          programData,
+          payer: recruiter.publicKey,
+        })
+        .signers([recruiter])
+        .rpc();
+
+      assert.fail("Should have rejected non-upgrade-authority");
+    } catch (err) {
+      assert.include(err.toString(), "UnauthorizedPlatformAccess");
+    }
+  });
+
+  it("Upgrade authority initializes config", async () => {
+    await program.methods
+      .initializeConfig(
+        provider.wallet.publicKey,
+        treasury,
+        feeBps,
+        arbitrator.publicKey,
+        disputeBond,
+        applicationBond
+      )
+      .accounts({
+        config: configPDA,
+        program: program.programId,
+        programData,
+        payer: provider.wallet.publicKey,
+      })
+      .rpc();
+
+    const config = await program.account.config.fetch(configPDA);
+    assert.equal(
+      config.authority.toBase58(),
+      provider.wallet.publicKey.toBase58()
+    );
+    assert.equal(config.treasury.toBase58(), treasury.toBase58());
+    assert.equal(config.feeBps, feeBps);
+    assert.equal(config.arbitrator.toBase58(), arbitrator.publicKey.toBase58());
+    assert.equal(config.disputeBond.toString(), disputeBond.toString());
+    assert.equal(
+      config.applicationBond.toString(),
+      applicationBond.toString()
+    );
+  });
+
   it("Creates job escrow and locks funds", async () => {
     const recruiterBalanceBefore = await provider.connection.getBalance(
       recruiter.publicKey
     );
 
     await program.methods
-      .createJobEscrow(jobId, freelancer.publicKey, milestoneAmounts)
+      .createJobEscrow(
+        jobId,
+        freelancer.publicKey,
+        milestoneAmounts,
+        noDueDates(milestoneAmounts),
+        reviewPeriod,
+        jobTerms()
+      )
       .accounts({
         escrow: escrowPDA,
+        jobCounter: jobCounterPDA(jobId),
         recruiter: recruiter.publicKey,
         systemProgram: anchor.web3.SystemProgram.programId,
       })
@@ -83,22 +333,31 @@ describe("freelance_platform", () => {
       escrowAccount.freelancer.toBase58(),
       freelancer.publicKey.toBase58()
     );
-    assert.equal(escrowAccount.jobId, jobId);
+    assert.deepEqual(escrowAccount.jobHash, Array.from(hashJobId(jobId)));
     assert.deepEqual(
       escrowAccount.milestoneAmounts.map((n) => n.toString()),
       milestoneAmounts.map((n) => n.toString())
     );
-    assert.deepEqual(escrowAccount.milestonesApproved, [
-      false,
-      false,
-      false,
-    ]);
-    assert.deepEqual(escrowAccount.milestonesClaimed, [false, false, false]);
+    assert.deepEqual(
+      escrowAccount.milestoneApprovedAmounts.map((n) => n.toNumber()),
+      [0, 0, 0]
+    );
+    assert.deepEqual(
+      escrowAccount.milestoneClaimedAmounts.map((n) => n.toNumber()),
+      [0, 0, 0]
+    );
+    assert.deepEqual(escrowAccount.status, { pending: {} });
+    assert.equal(escrowAccount.version, 1);
+    assert.equal(escrowAccount.feeBps, feeBps);
 
-    // Verify escrow PDA balance
-    const escrowBalance = await provider.connection.getBalance(escrowPDA);
+    // Verify the vault holds exactly the milestones on top of its reserve
+    const vaultBalance = await provider.connection.getBalance(
+      vaultPDA(escrowPDA)
+    );
+    const vaultReserve =
+      await provider.connection.getMinimumBalanceForRentExemption(0);
     const totalAmount = milestoneAmounts.reduce((a, b) => a.add(b), new BN(0));
-    assert.isAtLeast(escrowBalance, totalAmount.toNumber());
+    assert.equal(vaultBalance - vaultReserve, totalAmount.toNumber());
 
     // Verify recruiter paid
     const recruiterBalanceAfter = await provider.connection.getBalance(
@@ -107,22 +366,81 @@ describe("freelance_platform", () => {
     assert.isBelow(recruiterBalanceAfter, recruiterBalanceBefore);
   });
 
+  it("Freelancer accepts the job terms", async () => {
+    try {
+      await program.methods
+        .approveMilestone(0, milestoneAmounts[0])
+        .accounts({ escrow: escrowPDA, recruiter: recruiter.publicKey })
+        .signers([recruiter])
+        .rpc();
+
+      assert.fail("Should not approve before the freelancer accepts");
+    } catch (err) {
+      assert.include(err.toString(), "JobNotAccepted");
+    }
+
+    try {
+      await program.methods
+        .acceptJob(Array(32).fill(0))
+        .accounts({
+          escrow: escrowPDA,
+          config: configPDA,
+          feeTreasury: feeTreasuryPDA,
+          freelancer: freelancer.publicKey,
+        })
+        .signers([freelancer])
+        .rpc();
+
+      assert.fail("Should have rejected mismatched terms");
+    } catch (err) {
+      assert.include(err.toString(), "TermsHashMismatch");
+    }
+
+    const sig = await program.methods
+      .acceptJob(termsHash)
+      .accounts({
+        escrow: escrowPDA,
+        config: configPDA,
+        feeTreasury: feeTreasuryPDA,
+        freelancer: freelancer.publicKey,
+      })
+      .signers([freelancer])
+      .rpc({ commitment: "confirmed" });
+
+    const escrowAccount = await program.account.escrow.fetch(escrowPDA);
+    assert.deepEqual(escrowAccount.status, { funded: {} });
+
+    const [event] = await emittedEvents(sig);
+    assert.equal(event.name, "jobAccepted");
+    assert.ok(event.data.freelancer.equals(freelancer.publicKey));
+    assert.equal(event.data.advance.toNumber(), 0);
+  });
+
   it("Prevents creating escrow with invalid milestone amounts", async () => {
     const invalidAmounts = [new BN(0), new BN(1 * LAMPORTS_PER_SOL), new BN(2 * LAMPORTS_PER_SOL)];
     const invalidJobId = "invalid-job";
 
     try {
       await program.methods
-        .createJobEscrow(invalidJobId, freelancer.publicKey, invalidAmounts)
+        .createJobEscrow(
+          invalidJobId,
+          freelancer.publicKey,
+          invalidAmounts,
+          noDueDates(invalidAmounts),
+          reviewPeriod,
+          jobTerms()
+        )
         .accounts({
           escrow: PublicKey.findProgramAddressSync(
             [
               Buffer.from("escrow"),
               recruiter.publicKey.toBuffer(),
               hashJobId(invalidJobId),
+              generationSeed(0),
             ],
             program.programId
           )[0],
+          jobCounter: jobCounterPDA(invalidJobId),
           recruiter: recruiter.publicKey,
           systemProgram: anchor.web3.SystemProgram.programId,
         })
@@ -138,12 +456,149 @@ describe("freelance_platform", () => {
     }
   });
 
+  it("Creates escrows with a variable number of milestones", async () => {
+    for (const count of [1, 5]) {
+      const variableJobId = `variable-job-${count}`;
+      const [variableEscrowPDA] = PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("escrow"),
+          recruiter.publicKey.toBuffer(),
+          hashJobId(variableJobId),
+          generationSeed(0),
+        ],
+        program.programId
+      );
+      const amounts = Array.from(
+        { length: count },
+        () => new BN(0.1 * LAMPORTS_PER_SOL)
+      );
+
+      await program.methods
+        .createJobEscrow(
+          variableJobId,
+          freelancer.publicKey,
+          amounts,
+          noDueDates(amounts),
+          reviewPeriod,
+          jobTerms()
+        )
+        .accounts({
+          escrow: variableEscrowPDA,
+          jobCounter: jobCounterPDA(variableJobId),
+          recruiter: recruiter.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([recruiter])
+        .rpc();
+
+      const escrowAccount = await program.account.escrow.fetch(
+        variableEscrowPDA
+      );
+      assert.equal(escrowAccount.milestoneAmounts.length, count);
+      assert.deepEqual(
+        escrowAccount.milestoneApprovedAmounts.map((n) => n.toNumber()),
+        new Array(count).fill(0)
+      );
+      assert.deepEqual(
+        escrowAccount.milestoneClaimedAmounts.map((n) => n.toNumber()),
+        new Array(count).fill(0)
+      );
+    }
+  });
+
+  it("Accepts job IDs longer than 50 characters", async () => {
+    const longJobId = "long-job-" + "0123456789".repeat(10);
+    const [longEscrowPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("escrow"),
+        recruiter.publicKey.toBuffer(),
+        hashJobId(longJobId),
+        generationSeed(0),
+      ],
+      program.programId
+    );
+
+    await program.methods
+      .createJobEscrow(
+        longJobId,
+        freelancer.publicKey,
+        milestoneAmounts,
+        noDueDates(milestoneAmounts),
+        reviewPeriod,
+        jobTerms()
+      )
+      .accounts({
+        escrow: longEscrowPDA,
+        jobCounter: jobCounterPDA(longJobId),
+        recruiter: recruiter.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([recruiter])
+      .rpc();
+
+    const escrowAccount = await program.account.escrow.fetch(longEscrowPDA);
+    assert.deepEqual(escrowAccount.jobHash, Array.from(hashJobId(longJobId)));
+  });
+
+  it("Prevents creating escrow with no milestones", async () => {
+    const emptyJobId = "empty-job";
+
+    try {
+      await program.methods
+        .createJobEscrow(
+          emptyJobId,
+          freelancer.publicKey,
+          [],
+          [],
+          reviewPeriod,
+          jobTerms()
+        )
+        .accounts({
+          escrow: PublicKey.findProgramAddressSync(
+            [
+              Buffer.from("escrow"),
+              recruiter.publicKey.toBuffer(),
+              hashJobId(emptyJobId),
+              generationSeed(0),
+            ],
+            program.programId
+          )[0],
+          jobCounter: jobCounterPDA(emptyJobId),
+          recruiter: recruiter.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([recruiter])
+        .rpc();
+
+      assert.fail("Should have thrown error for empty milestone list");
+    } catch (err) {
+      assert.include(err.toString(), "InvalidMilestoneCount");
+    }
+  });
+
+  it("Prevents approving an out-of-range milestone", async () => {
+    try {
+      await program.methods
+        .approveMilestone(milestoneAmounts.length, new BN(1))
+        .accounts({
+          escrow: escrowPDA,
+          recruiter: recruiter.publicKey,
+        })
+        .signers([recruiter])
+        .rpc();
+
+      assert.fail("Should have rejected out-of-range milestone index");
+    } catch (err) {
+      assert.include(err.toString(), "InvalidMilestoneIndex");
+    }
+  });
+
   it("Prevents non-recruiter from approving milestone", async () => {
     const fakeRecruiter = Keypair.generate();
 
     try {
       await program.methods
-        .approveMilestone(0)
+        .approveMilestone(0, milestoneAmounts[0])
         .accounts({
           escrow: escrowPDA,
           recruiter: fakeRecruiter.publicKey,
@@ -166,27 +621,38 @@ describe("freelance_platform", () => {
   });
 
   it("Recruiter approves milestone 0", async () => {
-    await program.methods
-      .approveMilestone(0)
+    const sig = await program.methods
+      .approveMilestone(0, milestoneAmounts[0])
       .accounts({
         escrow: escrowPDA,
         recruiter: recruiter.publicKey,
       })
       .signers([recruiter])
-      .rpc();
+      .rpc({ commitment: "confirmed" });
 
     const escrowAccount = await program.account.escrow.fetch(escrowPDA);
-    assert.deepEqual(escrowAccount.milestonesApproved, [
-      true,
-      false,
-      false,
-    ]);
+    assert.deepEqual(
+      escrowAccount.milestoneApprovedAmounts.map((n) => n.toString()),
+      [milestoneAmounts[0].toString(), "0", "0"]
+    );
+    assert.deepEqual(escrowAccount.status, { inProgress: {} });
+
+    const [event] = await emittedEvents(sig);
+    assert.equal(event.name, "milestoneApproved");
+    assert.ok(event.data.escrow.equals(escrowPDA));
+    assert.deepEqual(event.data.jobHash, Array.from(hashJobId(jobId)));
+    assert.equal(event.data.milestoneIndex, 0);
+    assert.equal(
+      event.data.approvedAmount.toString(),
+      milestoneAmounts[0].toString()
+    );
+    assert.isFalse(event.data.autoApproved);
   });
 
   it("Prevents double approval of same milestone", async () => {
     try {
       await program.methods
-        .approveMilestone(0)
+        .approveMilestone(0, milestoneAmounts[0])
         .accounts({
           escrow: escrowPDA,
           recruiter: recruiter.publicKey,
@@ -200,6 +666,118 @@ describe("freelance_platform", () => {
     }
   });
 
+  it("Supports partial approval, partial claim and remainder refund", async () => {
+    const partialJobId = "partial-job";
+    const [partialEscrowPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("escrow"),
+        recruiter.publicKey.toBuffer(),
+        hashJobId(partialJobId),
+        generationSeed(0),
+      ],
+      program.programId
+    );
+    const amounts = [new BN(1 * LAMPORTS_PER_SOL)];
+
+    await program.methods
+      .createJobEscrow(
+        partialJobId,
+        freelancer.publicKey,
+        amounts,
+        noDueDates(amounts),
+        reviewPeriod,
+        jobTerms()
+      )
+      .accounts({
+        escrow: partialEscrowPDA,
+        jobCounter: jobCounterPDA(partialJobId),
+        recruiter: recruiter.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([recruiter])
+      .rpc();
+
+    await acceptJob(partialEscrowPDA);
+
+    const quarter = new BN(0.25 * LAMPORTS_PER_SOL);
+    const half = new BN(0.5 * LAMPORTS_PER_SOL);
+
+    await program.methods
+      .approveMilestone(0, quarter)
+      .accounts({ escrow: partialEscrowPDA, recruiter: recruiter.publicKey })
+      .signers([recruiter])
+      .rpc();
+
+    await program.methods
+      .claimMilestone(0)
+      .accounts({ escrow: partialEscrowPDA, freelancer: freelancer.publicKey })
+      .signers([freelancer])
+      .rpc();
+
+    // Approvals are cumulative and can only grow
+    try {
+      await program.methods
+        .approveMilestone(0, quarter)
+        .accounts({ escrow: partialEscrowPDA, recruiter: recruiter.publicKey })
+        .signers([recruiter])
+        .rpc();
+
+      assert.fail("Should have rejected non-increasing approval");
+    } catch (err) {
+      assert.include(err.toString(), "InvalidApprovalAmount");
+    }
+
+    await program.methods
+      .approveMilestone(0, half)
+      .accounts({ escrow: partialEscrowPDA, recruiter: recruiter.publicKey })
+      .signers([recruiter])
+      .rpc();
+
+    const escrowBalanceBefore = await provider.connection.getBalance(
+      vaultPDA(partialEscrowPDA)
+    );
+    await program.methods
+      .refundMilestoneRemainder(0)
+      .accounts({ escrow: partialEscrowPDA, recruiter: recruiter.publicKey })
+      .signers([recruiter])
+      .rpc();
+    const escrowBalanceAfter = await provider.connection.getBalance(
+      vaultPDA(partialEscrowPDA)
+    );
+    assert.equal(escrowBalanceBefore - escrowBalanceAfter, half.toNumber());
+
+    await program.methods
+      .claimMilestone(0)
+      .accounts({ escrow: partialEscrowPDA, freelancer: freelancer.publicKey })
+      .signers([freelancer])
+      .rpc();
+
+    const escrowAccount = await program.account.escrow.fetch(partialEscrowPDA);
+    assert.equal(escrowAccount.milestoneAmounts[0].toString(), half.toString());
+    assert.equal(
+      escrowAccount.milestoneClaimedAmounts[0].toString(),
+      half.toString()
+    );
+
+    // Fully settled: anyone can close it and the rent goes to the recruiter
+    const recruiterBalanceBefore = await provider.connection.getBalance(
+      recruiter.publicKey
+    );
+    await program.methods
+      .closeCompletedEscrow()
+      .accounts({ escrow: partialEscrowPDA, recruiter: recruiter.publicKey })
+      .rpc();
+    const recruiterBalanceAfter = await provider.connection.getBalance(
+      recruiter.publicKey
+    );
+
+    assert.isNull(await provider.connection.getAccountInfo(partialEscrowPDA));
+    assert.isNull(
+      await provider.connection.getAccountInfo(vaultPDA(partialEscrowPDA))
+    );
+    assert.isAbove(recruiterBalanceAfter, recruiterBalanceBefore);
+  });
+
   it("Prevents claiming unapproved milestone", async () => {
     try {
       await program.methods
@@ -233,7 +811,7 @@ describe("freelance_platform", () => {
       freelancer.publicKey
     );
     const escrowBalanceBefore = await provider.connection.getBalance(
-      escrowPDA
+      vaultPDA(escrowPDA)
     );
 
     await program.methods
@@ -248,26 +826,31 @@ describe("freelance_platform", () => {
     const freelancerBalanceAfter = await provider.connection.getBalance(
       freelancer.publicKey
     );
-    const escrowBalanceAfter = await provider.connection.getBalance(escrowPDA);
+    const escrowBalanceAfter = await provider.connection.getBalance(
+      vaultPDA(escrowPDA)
+    );
 
-    // Verify payment received
-    const expectedIncrease = milestoneAmounts[0].toNumber();
+    // Verify payment received, net of the platform fee
+    const fee = platformFee(milestoneAmounts[0]);
+    const expectedIncrease = milestoneAmounts[0].sub(fee).toNumber();
     assert.approximately(
       freelancerBalanceAfter - freelancerBalanceBefore,
       expectedIncrease,
       10000 // Allow small variance for tx fees
     );
 
-    // Verify escrow balance decreased
-    assert.approximately(
+    // Verify the vault decreased by exactly the full milestone
+    assert.equal(
       escrowBalanceBefore - escrowBalanceAfter,
-      expectedIncrease,
-      1000
+      milestoneAmounts[0].toNumber()
     );
 
     // Verify claimed status
     const escrowAccount = await program.account.escrow.fetch(escrowPDA);
-    assert.deepEqual(escrowAccount.milestonesClaimed, [true, false, false]);
+    assert.deepEqual(
+      escrowAccount.milestoneClaimedAmounts.map((n) => n.toString()),
+      [milestoneAmounts[0].toString(), "0", "0"]
+    );
   });
 
   it("Prevents double claiming same milestone", async () => {
@@ -292,7 +875,7 @@ describe("freelance_platform", () => {
 
     // Approve milestone 1 first
     await program.methods
-      .approveMilestone(1)
+      .approveMilestone(1, milestoneAmounts[1])
       .accounts({
         escrow: escrowPDA,
         recruiter: recruiter.publicKey,
@@ -337,7 +920,7 @@ describe("freelance_platform", () => {
 
     // Approve and claim milestone 2
     await program.methods
-      .approveMilestone(2)
+      .approveMilestone(2, milestoneAmounts[2])
       .accounts({
         escrow: escrowPDA,
         recruiter: recruiter.publicKey,
@@ -356,58 +939,503 @@ describe("freelance_platform", () => {
 
     // Verify all milestones completed
     const escrowAccount = await program.account.escrow.fetch(escrowPDA);
-    assert.deepEqual(escrowAccount.milestonesApproved, [true, true, true]);
-    assert.deepEqual(escrowAccount.milestonesClaimed, [true, true, true]);
-  });
-
-  it("Tests cancel job functionality", async () => {
-    // Create new job for cancel test
-    const cancelJobId = "cancel-test-job";
+    assert.deepEqual(
+      escrowAccount.milestoneApprovedAmounts.map((n) => n.toString()),
+      milestoneAmounts.map((n) => n.toString())
+    );
+    assert.deepEqual(
+      escrowAccount.milestoneClaimedAmounts.map((n) => n.toString()),
+      milestoneAmounts.map((n) => n.toString())
+    );
+  });
+
+  describe("milestone review window", () => {
+    const reviewJobId = "review-job";
+    const [reviewEscrowPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("escrow"),
+        recruiter.publicKey.toBuffer(),
+        hashJobId(reviewJobId),
+        generationSeed(0),
+      ],
+      program.programId
+    );
+
+    before(async () => {
+      await program.methods
+        .createJobEscrow(
+          reviewJobId,
+          freelancer.publicKey,
+          milestoneAmounts,
+          noDueDates(milestoneAmounts),
+          reviewPeriod,
+          jobTerms()
+        )
+        .accounts({
+          escrow: reviewEscrowPDA,
+          jobCounter: jobCounterPDA(reviewJobId),
+          recruiter: recruiter.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([recruiter])
+        .rpc();
+
+      await acceptJob(reviewEscrowPDA);
+    });
+
+    it("Prevents auto-approval of unsubmitted milestone", async () => {
+      try {
+        await program.methods
+          .autoApproveMilestone(0)
+          .accounts({ escrow: reviewEscrowPDA })
+          .rpc();
+
+        assert.fail("Should have rejected unsubmitted milestone");
+      } catch (err) {
+        assert.include(err.toString(), "MilestoneNotSubmitted");
+      }
+    });
+
+    it("Freelancer submits and recruiter rejects", async () => {
+      await program.methods
+        .submitMilestone(0)
+        .accounts({ escrow: reviewEscrowPDA, freelancer: freelancer.publicKey })
+        .signers([freelancer])
+        .rpc();
+
+      let escrowAccount = await program.account.escrow.fetch(reviewEscrowPDA);
+      assert.isAbove(escrowAccount.milestonesSubmittedAt[0].toNumber(), 0);
+
+      await program.methods
+        .rejectMilestone(0)
+        .accounts({ escrow: reviewEscrowPDA, recruiter: recruiter.publicKey })
+        .signers([recruiter])
+        .rpc();
+
+      escrowAccount = await program.account.escrow.fetch(reviewEscrowPDA);
+      assert.equal(escrowAccount.milestonesSubmittedAt[0].toNumber(), 0);
+    });
+
+    it("Prevents auto-approval inside the review window", async () => {
+      await program.methods
+        .submitMilestone(0)
+        .accounts({ escrow: reviewEscrowPDA, freelancer: freelancer.publicKey })
+        .signers([freelancer])
+        .rpc();
+
+      try {
+        await program.methods
+          .autoApproveMilestone(0)
+          .accounts({ escrow: reviewEscrowPDA })
+          .rpc();
+
+        assert.fail("Should have rejected auto-approval inside window");
+      } catch (err) {
+        assert.include(err.toString(), "ReviewWindowOpen");
+      }
+    });
+
+    it("Anyone auto-approves after the review window lapses", async () => {
+      await new Promise((resolve) =>
+        setTimeout(resolve, (reviewPeriod.toNumber() + 1) * 1000)
+      );
+
+      await program.methods
+        .autoApproveMilestone(0)
+        .accounts({ escrow: reviewEscrowPDA })
+        .rpc();
+
+      const escrowAccount = await program.account.escrow.fetch(reviewEscrowPDA);
+      assert.equal(
+        escrowAccount.milestoneApprovedAmounts[0].toString(),
+        milestoneAmounts[0].toString()
+      );
+    });
+  });
+
+  it("Recruiter reclaims milestones past their due date", async () => {
+    const dueJobId = "due-date-job";
+    const [dueEscrowPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("escrow"),
+        recruiter.publicKey.toBuffer(),
+        hashJobId(dueJobId),
+        generationSeed(0),
+      ],
+      program.programId
+    );
+    const now = Math.floor(Date.now() / 1000);
+    const dueDates = [new BN(0), new BN(now + 3), new BN(now + 3)];
+
+    await program.methods
+      .createJobEscrow(
+        dueJobId,
+        freelancer.publicKey,
+        milestoneAmounts,
+        dueDates,
+        reviewPeriod,
+        jobTerms()
+      )
+      .accounts({
+        escrow: dueEscrowPDA,
+        jobCounter: jobCounterPDA(dueJobId),
+        recruiter: recruiter.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([recruiter])
+      .rpc();
+
+    await acceptJob(dueEscrowPDA);
+
+    // Milestone 1 is approved in time, milestone 2 is abandoned
+    await program.methods
+      .approveMilestone(1, milestoneAmounts[1])
+      .accounts({ escrow: dueEscrowPDA, recruiter: recruiter.publicKey })
+      .signers([recruiter])
+      .rpc();
+
+    try {
+      await program.methods
+        .reclaimExpiredMilestones()
+        .accounts({ escrow: dueEscrowPDA, recruiter: recruiter.publicKey })
+        .signers([recruiter])
+        .rpc();
+
+      assert.fail("Should have nothing to reclaim before the due date");
+    } catch (err) {
+      assert.include(err.toString(), "NothingToReclaim");
+    }
+
+    await new Promise((resolve) => setTimeout(resolve, 5000));
+
+    const escrowBalanceBefore = await provider.connection.getBalance(
+      vaultPDA(dueEscrowPDA)
+    );
+    await program.methods
+      .reclaimExpiredMilestones()
+      .accounts({ escrow: dueEscrowPDA, recruiter: recruiter.publicKey })
+      .signers([recruiter])
+      .rpc();
+    const escrowBalanceAfter = await provider.connection.getBalance(
+      vaultPDA(dueEscrowPDA)
+    );
+
+    assert.equal(
+      escrowBalanceBefore - escrowBalanceAfter,
+      milestoneAmounts[2].toNumber()
+    );
+    const escrowAccount = await program.account.escrow.fetch(dueEscrowPDA);
+    assert.equal(escrowAccount.milestoneAmounts[2].toNumber(), 0);
+    assert.equal(
+      escrowAccount.milestoneAmounts[1].toString(),
+      milestoneAmounts[1].toString()
+    );
+
+    // The approved milestone is still claimable
+    await program.methods
+      .claimMilestone(1)
+      .accounts({ escrow: dueEscrowPDA, freelancer: freelancer.publicKey })
+      .signers([freelancer])
+      .rpc();
+
+    // Later amendments keep the reclaimed milestone at 0
+    const amended = [
+      new BN(0.5 * LAMPORTS_PER_SOL),
+      milestoneAmounts[1],
+      new BN(0),
+    ];
+    await program.methods
+      .amendEscrow(amended, dueDates)
+      .accounts({
+        escrow: dueEscrowPDA,
+        recruiter: recruiter.publicKey,
+        freelancer: freelancer.publicKey,
+      })
+      .signers([recruiter, freelancer])
+      .rpc();
+
+    const amendedAccount = await program.account.escrow.fetch(dueEscrowPDA);
+    assert.equal(
+      amendedAccount.milestoneAmounts[0].toString(),
+      amended[0].toString()
+    );
+    assert.equal(amendedAccount.milestoneAmounts[2].toNumber(), 0);
+  });
+
+  it("Amends milestones with both signatures", async () => {
+    const amendJobId = "amend-job";
+    const [amendEscrowPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("escrow"),
+        recruiter.publicKey.toBuffer(),
+        hashJobId(amendJobId),
+        generationSeed(0),
+      ],
+      program.programId
+    );
+    const amounts = [
+      new BN(0.5 * LAMPORTS_PER_SOL),
+      new BN(0.5 * LAMPORTS_PER_SOL),
+    ];
+
+    await program.methods
+      .createJobEscrow(
+        amendJobId,
+        freelancer.publicKey,
+        amounts,
+        noDueDates(amounts),
+        reviewPeriod,
+        jobTerms()
+      )
+      .accounts({
+        escrow: amendEscrowPDA,
+        jobCounter: jobCounterPDA(amendJobId),
+        recruiter: recruiter.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([recruiter])
+      .rpc();
+
+    await acceptJob(amendEscrowPDA);
+
+    await program.methods
+      .approveMilestone(0, amounts[0])
+      .accounts({ escrow: amendEscrowPDA, recruiter: recruiter.publicKey })
+      .signers([recruiter])
+      .rpc();
+
+    // Approved milestone 0 is locked
+    try {
+      const changed = [new BN(0.4 * LAMPORTS_PER_SOL), amounts[1]];
+      await program.methods
+        .amendEscrow(changed, noDueDates(changed))
+        .accounts({
+          escrow: amendEscrowPDA,
+          recruiter: recruiter.publicKey,
+          freelancer: freelancer.publicKey,
+        })
+        .signers([recruiter, freelancer])
+        .rpc();
+
+      assert.fail("Should have rejected amending an approved milestone");
+    } catch (err) {
+      assert.include(err.toString(), "ApprovedMilestoneLocked");
+    }
+
+    // Re-split the rest into two milestones and add 0.25 SOL overall
+    const amended = [
+      amounts[0],
+      new BN(0.25 * LAMPORTS_PER_SOL),
+      new BN(0.5 * LAMPORTS_PER_SOL),
+    ];
+    const escrowBalanceBefore = await provider.connection.getBalance(
+      vaultPDA(amendEscrowPDA)
+    );
+
+    await program.methods
+      .amendEscrow(amended, noDueDates(amended))
+      .accounts({
+        escrow: amendEscrowPDA,
+        recruiter: recruiter.publicKey,
+        freelancer: freelancer.publicKey,
+      })
+      .signers([recruiter, freelancer])
+      .rpc();
+
+    const escrowAccount = await program.account.escrow.fetch(amendEscrowPDA);
+    assert.deepEqual(
+      escrowAccount.milestoneAmounts.map((n) => n.toString()),
+      amended.map((n) => n.toString())
+    );
+    assert.equal(
+      escrowAccount.milestoneApprovedAmounts[0].toString(),
+      amounts[0].toString()
+    );
+
+    // The vault grows by exactly the extra 0.25 SOL; rent is paid separately
+    const escrowBalanceAfter = await provider.connection.getBalance(
+      vaultPDA(amendEscrowPDA)
+    );
+    assert.equal(
+      escrowBalanceAfter - escrowBalanceBefore,
+      0.25 * LAMPORTS_PER_SOL
+    );
+  });
+
+  it("Recruiter adds milestones to a live escrow", async () => {
+    const topUpJobId = "top-up-job";
+    const [topUpEscrowPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("escrow"),
+        recruiter.publicKey.toBuffer(),
+        hashJobId(topUpJobId),
+        generationSeed(0),
+      ],
+      program.programId
+    );
+    const amounts = [new BN(0.5 * LAMPORTS_PER_SOL)];
+
+    await program.methods
+      .createJobEscrow(
+        topUpJobId,
+        freelancer.publicKey,
+        amounts,
+        noDueDates(amounts),
+        reviewPeriod,
+        jobTerms()
+      )
+      .accounts({
+        escrow: topUpEscrowPDA,
+        jobCounter: jobCounterPDA(topUpJobId),
+        recruiter: recruiter.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([recruiter])
+      .rpc();
+
+    await acceptJob(topUpEscrowPDA);
+
+    await program.methods
+      .approveMilestone(0, amounts[0])
+      .accounts({ escrow: topUpEscrowPDA, recruiter: recruiter.publicKey })
+      .signers([recruiter])
+      .rpc();
+
+    const added = [
+      new BN(0.2 * LAMPORTS_PER_SOL),
+      new BN(0.3 * LAMPORTS_PER_SOL),
+    ];
+    await program.methods
+      .addMilestones(added, noDueDates(added))
+      .accounts({
+        escrow: topUpEscrowPDA,
+        recruiter: recruiter.publicKey,
+      })
+      .signers([recruiter])
+      .rpc();
+
+    const escrowAccount = await program.account.escrow.fetch(topUpEscrowPDA);
+    assert.deepEqual(
+      escrowAccount.milestoneAmounts.map((n) => n.toString()),
+      amounts.concat(added).map((n) => n.toString())
+    );
+    // Existing approval survives the reallocation
+    assert.deepEqual(
+      escrowAccount.milestoneApprovedAmounts.map((n) => n.toString()),
+      [amounts[0].toString(), "0", "0"]
+    );
+
+    try {
+      await program.methods
+        .closeCompletedEscrow()
+        .accounts({ escrow: topUpEscrowPDA, recruiter: recruiter.publicKey })
+        .rpc();
+
+      assert.fail("Should not close an escrow with unclaimed milestones");
+    } catch (err) {
+      assert.include(err.toString(), "EscrowNotSettled");
+    }
+  });
+
+  it("Tests cancel job functionality", async () => {
+    // Create new job for cancel test
+    const cancelJobId = "cancel-test-job";
     const [cancelEscrowPDA] = PublicKey.findProgramAddressSync(
       [
         Buffer.from("escrow"),
         recruiter.publicKey.toBuffer(),
         hashJobId(cancelJobId),
+        generationSeed(0),
       ],
       program.programId
     );
 
     // Create escrow
     await program.methods
-      .createJobEscrow(cancelJobId, freelancer.publicKey, milestoneAmounts)
+      .createJobEscrow(
+        cancelJobId,
+        freelancer.publicKey,
+        milestoneAmounts,
+        noDueDates(milestoneAmounts),
+        reviewPeriod,
+        jobTerms()
+      )
       .accounts({
         escrow: cancelEscrowPDA,
+        jobCounter: jobCounterPDA(cancelJobId),
         recruiter: recruiter.publicKey,
         systemProgram: anchor.web3.SystemProgram.programId,
       })
       .signers([recruiter])
       .rpc();
 
+    await acceptJob(cancelEscrowPDA);
+
     const recruiterBalanceBefore = await provider.connection.getBalance(
       recruiter.publicKey
     );
 
-    // Cancel job
+    // An accepted escrow is binding: the recruiter cannot cancel alone
+    try {
+      await program.methods
+        .cancelJob()
+        .accounts({
+          escrow: cancelEscrowPDA,
+          recruiter: recruiter.publicKey,
+          freelancer: null,
+        })
+        .signers([recruiter])
+        .rpc();
+
+      assert.fail("Should require the freelancer's signature");
+    } catch (err) {
+      assert.include(err.toString(), "FreelancerConsentRequired");
+    }
+
+    // Cancel job by mutual consent
     await program.methods
       .cancelJob()
       .accounts({
         escrow: cancelEscrowPDA,
         recruiter: recruiter.publicKey,
+        freelancer: freelancer.publicKey,
       })
-      .signers([recruiter])
+      .signers([recruiter, freelancer])
       .rpc();
 
-    // Verify escrow closed
-    const escrowAccountInfo = await provider.connection.getAccountInfo(
-      cancelEscrowPDA
-    );
-    assert.isNull(escrowAccountInfo);
+    // Escrow stays on-chain as a cancelled record until it is closed
+    const escrowAccount = await program.account.escrow.fetch(cancelEscrowPDA);
+    assert.deepEqual(escrowAccount.status, { cancelled: {} });
 
     // Verify recruiter received refund
     const recruiterBalanceAfter = await provider.connection.getBalance(
       recruiter.publicKey
     );
     assert.isAbove(recruiterBalanceAfter, recruiterBalanceBefore);
+
+    try {
+      await program.methods
+        .approveMilestone(0, milestoneAmounts[0])
+        .accounts({
+          escrow: cancelEscrowPDA,
+          recruiter: recruiter.publicKey,
+        })
+        .signers([recruiter])
+        .rpc();
+
+      assert.fail("Should not approve on a cancelled escrow");
+    } catch (err) {
+      assert.include(err.toString(), "EscrowNotActive");
+    }
+
+    await program.methods
+      .closeCompletedEscrow()
+      .accounts({ escrow: cancelEscrowPDA, recruiter: recruiter.publicKey })
+      .rpc();
+    assert.isNull(await provider.connection.getAccountInfo(cancelEscrowPDA));
+    assert.isNull(
+      await provider.connection.getAccountInfo(vaultPDA(cancelEscrowPDA))
+    );
   });
 
   it("Prevents canceling after approval", async () => {
@@ -417,23 +1445,34 @@ describe("freelance_platform", () => {
         Buffer.from("escrow"),
         recruiter.publicKey.toBuffer(),
         hashJobId(cancelJobId2),
+        generationSeed(0),
       ],
       program.programId
     );
 
     await program.methods
-      .createJobEscrow(cancelJobId2, freelancer.publicKey, milestoneAmounts)
+      .createJobEscrow(
+        cancelJobId2,
+        freelancer.publicKey,
+        milestoneAmounts,
+        noDueDates(milestoneAmounts),
+        reviewPeriod,
+        jobTerms()
+      )
       .accounts({
         escrow: cancelEscrowPDA2,
+        jobCounter: jobCounterPDA(cancelJobId2),
         recruiter: recruiter.publicKey,
         systemProgram: anchor.web3.SystemProgram.programId,
       })
       .signers([recruiter])
       .rpc();
 
+    await acceptJob(cancelEscrowPDA2);
+
     // Approve a milestone
     await program.methods
-      .approveMilestone(0)
+      .approveMilestone(0, milestoneAmounts[0])
       .accounts({
         escrow: cancelEscrowPDA2,
         recruiter: recruiter.publicKey,
@@ -447,8 +1486,9 @@ describe("freelance_platform", () => {
         .accounts({
           escrow: cancelEscrowPDA2,
           recruiter: recruiter.publicKey,
+          freelancer: freelancer.publicKey,
         })
-        .signers([recruiter])
+        .signers([recruiter, freelancer])
         .rpc();
 
       assert.fail("Should have prevented cancel after approval");
@@ -459,4 +1499,2755 @@ describe("freelance_platform", () => {
       );
     }
   });
-});
\ No newline at end of file
+
+  it("Prevents canceling while a submission awaits review", async () => {
+    const submittedJobId = "cancel-submitted-job";
+    const [submittedEscrowPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("escrow"),
+        recruiter.publicKey.toBuffer(),
+        hashJobId(submittedJobId),
+        generationSeed(0),
+      ],
+      program.programId
+    );
+
+    await program.methods
+      .createJobEscrow(
+        submittedJobId,
+        freelancer.publicKey,
+        milestoneAmounts,
+        noDueDates(milestoneAmounts),
+        reviewPeriod,
+        jobTerms()
+      )
+      .accounts({
+        escrow: submittedEscrowPDA,
+        jobCounter: jobCounterPDA(submittedJobId),
+        recruiter: recruiter.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([recruiter])
+      .rpc();
+
+    await acceptJob(submittedEscrowPDA);
+
+    await program.methods
+      .submitMilestone(0)
+      .accounts({
+        escrow: submittedEscrowPDA,
+        freelancer: freelancer.publicKey,
+      })
+      .signers([freelancer])
+      .rpc();
+
+    // Waiting out the review window must not let the recruiter refund
+    // themselves before anyone calls auto_approve_milestone
+    await new Promise((resolve) =>
+      setTimeout(resolve, (reviewPeriod.toNumber() + 1) * 1000)
+    );
+
+    try {
+      await program.methods
+        .cancelJob()
+        .accounts({
+          escrow: submittedEscrowPDA,
+          recruiter: recruiter.publicKey,
+          freelancer: freelancer.publicKey,
+        })
+        .signers([recruiter, freelancer])
+        .rpc();
+
+      assert.fail("Should have prevented cancel after submission");
+    } catch (err) {
+      assert.include(err.toString(), "CannotCancelAfterSubmission");
+    }
+  });
+
+  it("Re-funds a cancelled job at the next generation", async () => {
+    const recreateJobId = "recreate-job";
+    const escrowAt = (generation: number) =>
+      PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("escrow"),
+          recruiter.publicKey.toBuffer(),
+          hashJobId(recreateJobId),
+          generationSeed(generation),
+        ],
+        program.programId
+      )[0];
+    const create = (generation: number) =>
+      program.methods
+        .createJobEscrow(
+          recreateJobId,
+          freelancer.publicKey,
+          milestoneAmounts,
+          noDueDates(milestoneAmounts),
+          reviewPeriod,
+          jobTerms()
+        )
+        .accounts({
+          escrow: escrowAt(generation),
+          jobCounter: jobCounterPDA(recreateJobId),
+          recruiter: recruiter.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([recruiter])
+        .rpc();
+
+    await create(0);
+    await program.methods
+      .cancelJob()
+      .accounts({
+        escrow: escrowAt(0),
+        recruiter: recruiter.publicKey,
+        freelancer: null,
+      })
+      .signers([recruiter])
+      .rpc();
+
+    await create(1);
+
+    const counter = await program.account.jobCounter.fetch(
+      jobCounterPDA(recreateJobId)
+    );
+    assert.equal(counter.escrowCount, 2);
+    const current = await program.account.escrow.fetch(escrowAt(1));
+    assert.equal(current.generation, 1);
+    assert.deepEqual(current.status, { pending: {} });
+    const previous = await program.account.escrow.fetch(escrowAt(0));
+    assert.deepEqual(previous.status, { cancelled: {} });
+  });
+
+  describe("job acceptance", () => {
+    const advance = new BN(0.4 * LAMPORTS_PER_SOL);
+    const amounts = [new BN(0.5 * LAMPORTS_PER_SOL)];
+    const createPending = async (pendingJobId: string, acceptWithin?: number) => {
+      const [pendingEscrowPDA] = PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("escrow"),
+          recruiter.publicKey.toBuffer(),
+          hashJobId(pendingJobId),
+          generationSeed(0),
+        ],
+        program.programId
+      );
+      await program.methods
+        .createJobEscrow(
+          pendingJobId,
+          freelancer.publicKey,
+          amounts,
+          noDueDates(amounts),
+          reviewPeriod,
+          jobTerms(advance, acceptWithin)
+        )
+        .accounts({
+          escrow: pendingEscrowPDA,
+          jobCounter: jobCounterPDA(pendingJobId),
+          recruiter: recruiter.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([recruiter])
+        .rpc();
+      return pendingEscrowPDA;
+    };
+
+    it("Pays the advance to the freelancer on acceptance", async () => {
+      const advanceEscrowPDA = await createPending("advance-job");
+
+      const before = await provider.connection.getBalance(freelancer.publicKey);
+      await acceptJob(advanceEscrowPDA);
+      const after = await provider.connection.getBalance(freelancer.publicKey);
+
+      assert.equal(after - before, advance.sub(platformFee(advance)).toNumber());
+      const escrowAccount = await program.account.escrow.fetch(
+        advanceEscrowPDA
+      );
+      assert.deepEqual(escrowAccount.status, { funded: {} });
+    });
+
+    it("Recruiter withdraws a pending escrow, advance included", async () => {
+      const pendingEscrowPDA = await createPending("pending-cancel-job");
+
+      const before = await provider.connection.getBalance(recruiter.publicKey);
+      await program.methods
+        .cancelJob()
+        .accounts({
+          escrow: pendingEscrowPDA,
+          recruiter: recruiter.publicKey,
+          freelancer: null,
+        })
+        .signers([recruiter])
+        .rpc();
+      const after = await provider.connection.getBalance(recruiter.publicKey);
+
+      assert.equal(after - before, amounts[0].add(advance).toNumber());
+      const escrowAccount = await program.account.escrow.fetch(
+        pendingEscrowPDA
+      );
+      assert.deepEqual(escrowAccount.status, { cancelled: {} });
+    });
+
+    it("Anyone refunds an escrow that was not accepted in time", async () => {
+      const unacceptedEscrowPDA = await createPending("unaccepted-job", 2);
+      const refund = () =>
+        program.methods
+          .refundUnacceptedJob()
+          .accounts({
+            escrow: unacceptedEscrowPDA,
+            recruiter: recruiter.publicKey,
+          })
+          .rpc();
+
+      try {
+        await refund();
+        assert.fail("Should wait for the acceptance deadline");
+      } catch (err) {
+        assert.include(err.toString(), "AcceptanceDeadlineNotReached");
+      }
+
+      await new Promise((resolve) => setTimeout(resolve, 3000));
+
+      try {
+        await acceptJob(unacceptedEscrowPDA);
+        assert.fail("Should not accept after the deadline");
+      } catch (err) {
+        assert.include(err.toString(), "AcceptanceDeadlinePassed");
+      }
+
+      const before = await provider.connection.getBalance(recruiter.publicKey);
+      await refund();
+      const after = await provider.connection.getBalance(recruiter.publicKey);
+
+      assert.equal(after - before, amounts[0].add(advance).toNumber());
+      const escrowAccount = await program.account.escrow.fetch(
+        unacceptedEscrowPDA
+      );
+      assert.deepEqual(escrowAccount.status, { cancelled: {} });
+    });
+  });
+
+  describe("application bonds", () => {
+    const bondJobId = "bonded-job";
+    const rival = Keypair.generate();
+    const amounts = [new BN(0.5 * LAMPORTS_PER_SOL)];
+    const [bondEscrowPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("escrow"),
+        recruiter.publicKey.toBuffer(),
+        hashJobId(bondJobId),
+        generationSeed(0),
+      ],
+      program.programId
+    );
+
+    before(async () => {
+      const sig = await provider.connection.requestAirdrop(
+        rival.publicKey,
+        LAMPORTS_PER_SOL
+      );
+      await provider.connection.confirmTransaction(sig);
+    });
+
+    it("Rejects a bond below the platform minimum", async () => {
+      try {
+        await applyToJob(bondJobId, rival, applicationBond.subn(1));
+        assert.fail("Should have rejected the bond");
+      } catch (err) {
+        assert.include(err.toString(), "InsufficientApplicationBond");
+      }
+    });
+
+    it("Rejected applicant reclaims their bond", async () => {
+      const rivalApplicationPDA = applicationPDA(bondJobId, rival.publicKey);
+      await applyToJob(bondJobId, rival);
+
+      const application = await program.account.application.fetch(
+        rivalApplicationPDA
+      );
+      assert.equal(application.bond.toString(), applicationBond.toString());
+      assert.isFalse(application.rejected);
+
+      const reclaim = () =>
+        program.methods
+          .reclaimApplicationBond()
+          .accounts({
+            application: rivalApplicationPDA,
+            freelancer: rival.publicKey,
+          })
+          .signers([rival])
+          .rpc();
+
+      try {
+        await reclaim();
+        assert.fail("Should keep the bond locked until rejection");
+      } catch (err) {
+        assert.include(err.toString(), "ApplicationBondLocked");
+      }
+
+      await program.methods
+        .rejectApplication()
+        .accounts({
+          application: rivalApplicationPDA,
+          recruiter: recruiter.publicKey,
+        })
+        .signers([recruiter])
+        .rpc();
+
+      const before = await provider.connection.getBalance(rival.publicKey);
+      await reclaim();
+      const after = await provider.connection.getBalance(rival.publicKey);
+
+      assert.isAtLeast(after - before, applicationBond.toNumber());
+      assert.isNull(
+        await provider.connection.getAccountInfo(rivalApplicationPDA)
+      );
+    });
+
+    it("Rolls the selected freelancer's bond into the escrow", async () => {
+      await applyToJob(bondJobId, freelancer);
+      await program.methods
+        .createJobEscrow(
+          bondJobId,
+          freelancer.publicKey,
+          amounts,
+          noDueDates(amounts),
+          reviewPeriod,
+          jobTerms()
+        )
+        .accounts({
+          escrow: bondEscrowPDA,
+          jobCounter: jobCounterPDA(bondJobId),
+          recruiter: recruiter.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([recruiter])
+        .rpc();
+
+      try {
+        await rollApplicationBond(bondJobId, bondEscrowPDA);
+        assert.fail("Should wait for the freelancer to accept");
+      } catch (err) {
+        assert.include(err.toString(), "JobNotAccepted");
+      }
+
+      await acceptJob(bondEscrowPDA);
+      const vaultBefore = await provider.connection.getBalance(
+        vaultPDA(bondEscrowPDA)
+      );
+      await rollApplicationBond(bondJobId, bondEscrowPDA);
+      const vaultAfter = await provider.connection.getBalance(
+        vaultPDA(bondEscrowPDA)
+      );
+
+      assert.equal(vaultAfter - vaultBefore, applicationBond.toNumber());
+      const escrowAccount = await program.account.escrow.fetch(bondEscrowPDA);
+      assert.equal(
+        escrowAccount.collateral.toString(),
+        applicationBond.toString()
+      );
+      assert.isNull(
+        await provider.connection.getAccountInfo(
+          applicationPDA(bondJobId, freelancer.publicKey)
+        )
+      );
+    });
+
+    it("Refuses to roll a rejected application's bond", async () => {
+      const application = applicationPDA(bondJobId, freelancer.publicKey);
+      await applyToJob(bondJobId, freelancer);
+      await program.methods
+        .rejectApplication()
+        .accounts({ application, recruiter: recruiter.publicKey })
+        .signers([recruiter])
+        .rpc();
+
+      try {
+        await rollApplicationBond(bondJobId, bondEscrowPDA);
+        assert.fail("Should leave a rejected bond to its applicant");
+      } catch (err) {
+        assert.include(err.toString(), "ApplicationRejected");
+      }
+
+      await program.methods
+        .reclaimApplicationBond()
+        .accounts({ application, freelancer: freelancer.publicKey })
+        .signers([freelancer])
+        .rpc();
+      assert.isNull(await provider.connection.getAccountInfo(application));
+    });
+
+    it("Returns the collateral once the escrow settles", async () => {
+      const release = () =>
+        program.methods
+          .releaseCollateral()
+          .accounts({
+            escrow: bondEscrowPDA,
+            freelancer: freelancer.publicKey,
+          })
+          .rpc();
+
+      try {
+        await release();
+        assert.fail("Should hold the collateral while the job is live");
+      } catch (err) {
+        assert.include(err.toString(), "EscrowNotSettled");
+      }
+
+      await program.methods
+        .cancelJob()
+        .accounts({
+          escrow: bondEscrowPDA,
+          recruiter: recruiter.publicKey,
+          freelancer: freelancer.publicKey,
+        })
+        .signers([recruiter, freelancer])
+        .rpc();
+
+      const close = () =>
+        program.methods
+          .closeCompletedEscrow()
+          .accounts({ escrow: bondEscrowPDA, recruiter: recruiter.publicKey })
+          .rpc();
+
+      try {
+        await close();
+        assert.fail("Should not close over unsettled collateral");
+      } catch (err) {
+        assert.include(err.toString(), "CollateralNotSettled");
+      }
+
+      const before = await provider.connection.getBalance(freelancer.publicKey);
+      await release();
+      const after = await provider.connection.getBalance(freelancer.publicKey);
+      assert.equal(after - before, applicationBond.toNumber());
+
+      await close();
+      assert.isNull(await provider.connection.getAccountInfo(bondEscrowPDA));
+    });
+  });
+
+  it("Migrates a version 0 escrow to the current layout", async () => {
+    // Preloaded by the test validator from tests/fixtures/legacy_escrow.json:
+    // three milestones, the first approved and claimed, the second approved
+    const legacyEscrowPDA = new PublicKey(
+      "AXyzLyCLML5tm16mokoEaxvrNzf6hdDiJPbB1bDSj342"
+    );
+
+    await program.methods
+      .migrateEscrow()
+      .accounts({
+        escrow: legacyEscrowPDA,
+        payer: provider.wallet.publicKey,
+      })
+      .rpc();
+
+    const escrowAccount = await program.account.escrow.fetch(legacyEscrowPDA);
+    assert.equal(escrowAccount.version, 1);
+    assert.deepEqual(
+      escrowAccount.jobHash,
+      Array.from(hashJobId("legacy-job"))
+    );
+    assert.deepEqual(
+      escrowAccount.milestoneApprovedAmounts.map((n) => n.toString()),
+      ["1000000000", "1500000000", "0"]
+    );
+    assert.deepEqual(
+      escrowAccount.milestoneClaimedAmounts.map((n) => n.toString()),
+      ["1000000000", "0", "0"]
+    );
+    assert.deepEqual(escrowAccount.status, { inProgress: {} });
+
+    try {
+      await program.methods
+        .migrateEscrow()
+        .accounts({
+          escrow: legacyEscrowPDA,
+          payer: provider.wallet.publicKey,
+        })
+        .rpc();
+
+      assert.fail("Should not migrate twice");
+    } catch (err) {
+      assert.include(err.toString(), "EscrowAlreadyMigrated");
+    }
+  });
+
+  it("Claims from a version 0 escrow, upgrading it on the way", async () => {
+    // Preloaded from tests/fixtures/legacy_lazy_escrow.json: milestones of
+    // 1, 1.5 and 2 SOL, the first approved. The parties' keys come from
+    // fixed seeds so the test can sign for them.
+    const legacyEscrowPDA = new PublicKey(
+      "AKd8DQvsrsZ544RMp31n7WF7TWHj3rkjkkfTGcf5oPLk"
+    );
+    const legacyFreelancer = Keypair.fromSeed(new Uint8Array(32).fill(2));
+    const sig = await provider.connection.requestAirdrop(
+      legacyFreelancer.publicKey,
+      LAMPORTS_PER_SOL
+    );
+    await provider.connection.confirmTransaction(sig);
+
+    // Other instructions need an explicit migrate_escrow first
+    try {
+      await program.methods
+        .submitMilestone(1)
+        .accounts({
+          escrow: legacyEscrowPDA,
+          freelancer: legacyFreelancer.publicKey,
+        })
+        .signers([legacyFreelancer])
+        .rpc();
+
+      assert.fail("Should have required migrating the escrow first");
+    } catch (err) {
+      assert.include(err.toString(), "EscrowNotMigrated");
+    }
+
+    const before = await provider.connection.getBalance(
+      legacyFreelancer.publicKey
+    );
+    await program.methods
+      .claimMilestone(0)
+      .accounts({
+        escrow: legacyEscrowPDA,
+        freelancer: legacyFreelancer.publicKey,
+      })
+      .signers([legacyFreelancer])
+      .rpc();
+    const after = await provider.connection.getBalance(
+      legacyFreelancer.publicKey
+    );
+
+    // The claimant pays the upgrade rent, and version 0 escrows carry no fee
+    assert.isAbove(after - before, 0.99 * LAMPORTS_PER_SOL);
+    const escrowAccount = await program.account.escrow.fetch(legacyEscrowPDA);
+    assert.equal(escrowAccount.version, 1);
+    assert.equal(escrowAccount.feeBps, 0);
+    assert.deepEqual(
+      escrowAccount.milestoneClaimedAmounts.map((n) => n.toString()),
+      ["1000000000", "0", "0"]
+    );
+    const vaultBalance = await provider.connection.getBalance(
+      vaultPDA(legacyEscrowPDA)
+    );
+    const reserve =
+      await provider.connection.getMinimumBalanceForRentExemption(0);
+    assert.equal(vaultBalance - reserve, 3.5 * LAMPORTS_PER_SOL);
+  });
+
+  it("Recruiter alone cancels a version 0 escrow, upgrading it on the way", async () => {
+    // Preloaded from tests/fixtures/legacy_unaccepted_escrow.json: milestones
+    // of 1, 1.5 and 2 SOL, none approved. Version 0 had no acceptance step,
+    // so the freelancer's signature is not needed.
+    const legacyEscrowPDA = new PublicKey(
+      "3KDU9w8aVK17pHLbujbR7Prvz3ERCcN9q4GMkHJTpdgm"
+    );
+    const legacyRecruiter = Keypair.fromSeed(new Uint8Array(32).fill(1));
+    const sig = await provider.connection.requestAirdrop(
+      legacyRecruiter.publicKey,
+      LAMPORTS_PER_SOL
+    );
+    await provider.connection.confirmTransaction(sig);
+
+    const before = await provider.connection.getBalance(
+      legacyRecruiter.publicKey
+    );
+    await program.methods
+      .cancelJob()
+      .accounts({
+        escrow: legacyEscrowPDA,
+        config: configPDA,
+        recruiter: legacyRecruiter.publicKey,
+        freelancer: null,
+      })
+      .signers([legacyRecruiter])
+      .rpc();
+    const after = await provider.connection.getBalance(
+      legacyRecruiter.publicKey
+    );
+
+    // The refund less the upgrade rent and vault reserve the recruiter pays
+    assert.isAbove(after - before, 4.49 * LAMPORTS_PER_SOL);
+    const escrowAccount = await program.account.escrow.fetch(legacyEscrowPDA);
+    assert.equal(escrowAccount.version, 1);
+    assert.deepEqual(escrowAccount.status, { cancelled: {} });
+  });
+
+  describe("platform config", () => {
+    it("Prevents non-authority from updating config", async () => {
+      try {
+        await program.methods
+          .updateConfig(
+            arbitrator.publicKey,
+            disputeBond,
+            applicationBond
+          )
+          .accounts({
+            config: configPDA,
+            authority: recruiter.publicKey,
+          })
+          .signers([recruiter])
+          .rpc();
+
+        assert.fail("Should have rejected non-authority update");
+      } catch (err) {
+        assert.include(err.toString(), "UnauthorizedPlatformAccess");
+      }
+    });
+
+    it("Pauses and resumes escrow creation", async () => {
+      const setPaused = (creates: boolean) =>
+        program.methods
+          .setPauseFlags({
+            creates,
+            claims: false,
+            cancellations: false,
+            amendments: false,
+          })
+          .accounts({
+            config: configPDA,
+            authority: provider.wallet.publicKey,
+          })
+          .rpc();
+      const pausedJobId = "paused-job";
+      const [pausedEscrowPDA] = PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("escrow"),
+          recruiter.publicKey.toBuffer(),
+          hashJobId(pausedJobId),
+          generationSeed(0),
+        ],
+        program.programId
+      );
+      const create = () =>
+        program.methods
+          .createJobEscrow(
+            pausedJobId,
+            freelancer.publicKey,
+            [new BN(LAMPORTS_PER_SOL / 10)],
+            [new BN(0)],
+            reviewPeriod,
+            jobTerms()
+          )
+          .accounts({
+            escrow: pausedEscrowPDA,
+            config: configPDA,
+            jobCounter: jobCounterPDA(pausedJobId),
+            recruiter: recruiter.publicKey,
+          })
+          .signers([recruiter])
+          .rpc();
+
+      await setPaused(true);
+      try {
+        await create();
+        assert.fail("Should not create escrows while paused");
+      } catch (err) {
+        assert.include(err.toString(), "PlatformPaused");
+      }
+
+      await setPaused(false);
+      await create();
+      const escrowAccount = await program.account.escrow.fetch(pausedEscrowPDA);
+      assert.deepEqual(
+        escrowAccount.jobHash,
+        Array.from(hashJobId(pausedJobId))
+      );
+    });
+
+    it("Freezes acceptance while claims are paused", async () => {
+      const setClaimsPaused = (claims: boolean) =>
+        program.methods
+          .setPauseFlags({
+            creates: false,
+            claims,
+            cancellations: false,
+            amendments: false,
+          })
+          .accounts({
+            config: configPDA,
+            authority: provider.wallet.publicKey,
+          })
+          .rpc();
+      const [pausedEscrowPDA] = PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("escrow"),
+          recruiter.publicKey.toBuffer(),
+          hashJobId("paused-job"),
+          generationSeed(0),
+        ],
+        program.programId
+      );
+
+      await setClaimsPaused(true);
+      try {
+        await acceptJob(pausedEscrowPDA);
+        assert.fail("Should not pay out an advance while paused");
+      } catch (err) {
+        assert.include(err.toString(), "PlatformPaused");
+      }
+
+      await setClaimsPaused(false);
+      await acceptJob(pausedEscrowPDA);
+    });
+
+    it("Freezes top-ups and recruiter refunds while paused", async () => {
+      const setPaused = (paused: boolean) =>
+        program.methods
+          .setPauseFlags({
+            creates: false,
+            claims: false,
+            cancellations: paused,
+            amendments: paused,
+          })
+          .accounts({
+            config: configPDA,
+            authority: provider.wallet.publicKey,
+          })
+          .rpc();
+      const [pausedEscrowPDA] = PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("escrow"),
+          recruiter.publicKey.toBuffer(),
+          hashJobId("paused-job"),
+          generationSeed(0),
+        ],
+        program.programId
+      );
+      const added = [new BN(LAMPORTS_PER_SOL / 10)];
+
+      await setPaused(true);
+      try {
+        await program.methods
+          .addMilestones(added, noDueDates(added))
+          .accounts({
+            escrow: pausedEscrowPDA,
+            config: configPDA,
+            recruiter: recruiter.publicKey,
+          })
+          .signers([recruiter])
+          .rpc();
+        assert.fail("Should not top up escrows while paused");
+      } catch (err) {
+        assert.include(err.toString(), "PlatformPaused");
+      }
+      try {
+        await program.methods
+          .reclaimExpiredMilestones()
+          .accounts({
+            escrow: pausedEscrowPDA,
+            config: configPDA,
+            recruiter: recruiter.publicKey,
+          })
+          .signers([recruiter])
+          .rpc();
+        assert.fail("Should not refund the recruiter while paused");
+      } catch (err) {
+        assert.include(err.toString(), "PlatformPaused");
+      }
+
+      await setPaused(false);
+    });
+
+    it("Rotates the platform authority in two steps", async () => {
+      const newAuthority = Keypair.generate();
+      const propose = (from: PublicKey, to: PublicKey) =>
+        program.methods
+          .proposeAuthorityTransfer(to)
+          .accounts({ config: configPDA, authority: from });
+      const accept = (signer: Keypair | null, key: PublicKey) =>
+        program.methods
+          .acceptAuthorityTransfer()
+          .accounts({ config: configPDA, newAuthority: key })
+          .signers(signer ? [signer] : [])
+          .rpc();
+
+      await propose(provider.wallet.publicKey, newAuthority.publicKey).rpc();
+      let config = await program.account.config.fetch(configPDA);
+      assert.equal(
+        config.pendingAuthority.toBase58(),
+        newAuthority.publicKey.toBase58()
+      );
+      // Proposing alone does not hand over control
+      assert.equal(
+        config.authority.toBase58(),
+        provider.wallet.publicKey.toBase58()
+      );
+
+      try {
+        await accept(recruiter, recruiter.publicKey);
+        assert.fail("Should have rejected a key that was not nominated");
+      } catch (err) {
+        assert.include(err.toString(), "NotPendingAuthority");
+      }
+
+      await accept(newAuthority, newAuthority.publicKey);
+      config = await program.account.config.fetch(configPDA);
+      assert.equal(
+        config.authority.toBase58(),
+        newAuthority.publicKey.toBase58()
+      );
+      assert.isNull(config.pendingAuthority);
+
+      // Hand control back for the remaining tests
+      await propose(newAuthority.publicKey, provider.wallet.publicKey)
+        .signers([newAuthority])
+        .rpc();
+      await accept(null, provider.wallet.publicKey);
+    });
+  });
+
+  describe("platform multisig", () => {
+    const multisigJobId = "multisig-job";
+    const [multisigEscrowPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("escrow"),
+        recruiter.publicKey.toBuffer(),
+        hashJobId(multisigJobId),
+        generationSeed(0),
+      ],
+      program.programId
+    );
+
+    before(async () => {
+      for (const owner of owners) {
+        const sig = await provider.connection.requestAirdrop(
+          owner.publicKey,
+          LAMPORTS_PER_SOL
+        );
+        await provider.connection.confirmTransaction(sig);
+      }
+
+      await program.methods
+        .createJobEscrow(
+          multisigJobId,
+          freelancer.publicKey,
+          milestoneAmounts,
+          noDueDates(milestoneAmounts),
+          reviewPeriod,
+          jobTerms()
+        )
+        .accounts({
+          escrow: multisigEscrowPDA,
+          jobCounter: jobCounterPDA(multisigJobId),
+          recruiter: recruiter.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([recruiter])
+        .rpc();
+
+      await acceptJob(multisigEscrowPDA);
+    });
+
+    it("Config authority initializes a 2-of-3 multisig", async () => {
+      await program.methods
+        .initializeMultisig(
+          owners.map((owner) => owner.publicKey),
+          2,
+          timelockDelay
+        )
+        .accounts({
+          multisig: multisigPDA,
+          config: configPDA,
+          authority: provider.wallet.publicKey,
+        })
+        .rpc();
+
+      const multisig = await program.account.multisig.fetch(multisigPDA);
+      assert.equal(multisig.threshold, 2);
+      assert.equal(multisig.owners.length, 3);
+    });
+
+    it("Prevents non-owners from proposing", async () => {
+      try {
+        await program.methods
+          .proposePlatformAction({
+            emergencyClose: { escrow: multisigEscrowPDA },
+          })
+          .accounts({
+            multisig: multisigPDA,
+            proposal: proposalPDA(0),
+            escrow: multisigEscrowPDA,
+            proposer: recruiter.publicKey,
+          })
+          .signers([recruiter])
+          .rpc();
+
+        assert.fail("Should have rejected non-owner proposal");
+      } catch (err) {
+        assert.include(err.toString(), "NotMultisigOwner");
+      }
+    });
+
+    it("Any owner can cancel a pending proposal", async () => {
+      await program.methods
+        .proposePlatformAction({
+          flagEscrow: { escrow: multisigEscrowPDA },
+        })
+        .accounts({
+          multisig: multisigPDA,
+          proposal: proposalPDA(0),
+          escrow: multisigEscrowPDA,
+          proposer: owners[0].publicKey,
+        })
+        .signers([owners[0]])
+        .rpc();
+
+      await program.methods
+        .cancelProposal()
+        .accounts({
+          multisig: multisigPDA,
+          proposal: proposalPDA(0),
+          proposer: owners[0].publicKey,
+          owner: owners[2].publicKey,
+        })
+        .signers([owners[2]])
+        .rpc();
+
+      assert.isNull(await provider.connection.getAccountInfo(proposalPDA(0)));
+      const escrowAccount = await program.account.escrow.fetch(
+        multisigEscrowPDA
+      );
+      assert.deepEqual(escrowAccount.status, { funded: {} });
+    });
+
+    it("Flags an escrow through the multisig and lets the authority unflag it", async () => {
+      const proposal = await nextProposalPDA();
+      await program.methods
+        .proposePlatformAction({
+          flagEscrow: { escrow: multisigEscrowPDA },
+        })
+        .accounts({
+          multisig: multisigPDA,
+          proposal,
+          escrow: multisigEscrowPDA,
+          proposer: owners[0].publicKey,
+        })
+        .signers([owners[0]])
+        .rpc();
+
+      const flag = () =>
+        program.methods
+          .flagEscrow()
+          .accounts({
+            escrow: multisigEscrowPDA,
+            multisig: multisigPDA,
+            proposal,
+            proposer: owners[0].publicKey,
+          })
+          .rpc();
+
+      try {
+        await flag();
+        assert.fail("A single owner should not be able to freeze an escrow");
+      } catch (err) {
+        assert.include(err.toString(), "ProposalNotApproved");
+      }
+
+      // Flagging carries no notice period once the threshold is met
+      await program.methods
+        .approveProposal()
+        .accounts({
+          multisig: multisigPDA,
+          proposal,
+          owner: owners[1].publicKey,
+        })
+        .signers([owners[1]])
+        .rpc();
+      await flag();
+
+      let escrowAccount = await program.account.escrow.fetch(multisigEscrowPDA);
+      assert.deepEqual(escrowAccount.status, { flagged: {} });
+
+      try {
+        await program.methods
+          .submitMilestone(0)
+          .accounts({
+            escrow: multisigEscrowPDA,
+            freelancer: freelancer.publicKey,
+          })
+          .signers([freelancer])
+          .rpc();
+
+        assert.fail("Should have frozen the flagged escrow");
+      } catch (err) {
+        assert.include(err.toString(), "EscrowFlagged");
+      }
+
+      await program.methods
+        .unflagEscrow()
+        .accounts({
+          escrow: multisigEscrowPDA,
+          config: configPDA,
+          authority: provider.wallet.publicKey,
+        })
+        .rpc();
+      escrowAccount = await program.account.escrow.fetch(multisigEscrowPDA);
+      assert.deepEqual(escrowAccount.status, { funded: {} });
+    });
+
+    it("Emergency-closes a flagged escrow after the threshold and notice period", async () => {
+      await program.methods
+        .approveMilestone(0, milestoneAmounts[0])
+        .accounts({
+          escrow: multisigEscrowPDA,
+          recruiter: recruiter.publicKey,
+        })
+        .signers([recruiter])
+        .rpc();
+
+      const propose = async (proposal: PublicKey) =>
+        program.methods
+          .proposePlatformAction({
+            emergencyClose: { escrow: multisigEscrowPDA },
+          })
+          .accounts({
+            multisig: multisigPDA,
+            proposal,
+            escrow: multisigEscrowPDA,
+            proposer: owners[0].publicKey,
+          })
+          .signers([owners[0]])
+          .rpc();
+
+      // The notice period must run while the escrow is already frozen
+      try {
+        await propose(await nextProposalPDA());
+        assert.fail("Should only propose against a disputed or flagged escrow");
+      } catch (err) {
+        assert.include(err.toString(), "PlatformActionNotAllowed");
+      }
+
+      await flagEscrow(multisigEscrowPDA);
+      const proposal = await nextProposalPDA();
+      await propose(proposal);
+
+      const execute = () =>
+        program.methods
+          .platformEmergencyClose()
+          .accounts({
+            escrow: multisigEscrowPDA,
+            multisig: multisigPDA,
+            proposal,
+            feeTreasury: feeTreasuryPDA,
+            dispute: null,
+            freelancer: freelancer.publicKey,
+            recruiter: recruiter.publicKey,
+            openedBy: null,
+            proposer: owners[0].publicKey,
+          })
+          .rpc({ commitment: "confirmed" });
+
+      try {
+        await execute();
+        assert.fail("Should require a second approval");
+      } catch (err) {
+        assert.include(err.toString(), "ProposalNotApproved");
+      }
+
+      await program.methods
+        .approveProposal()
+        .accounts({
+          multisig: multisigPDA,
+          proposal,
+          owner: owners[1].publicKey,
+        })
+        .signers([owners[1]])
+        .rpc();
+
+      const queued = await program.account.proposal.fetch(proposal);
+      assert.isAbove(queued.executableAt.toNumber(), 0);
+
+      try {
+        await execute();
+        assert.fail("Should wait out the notice period");
+      } catch (err) {
+        assert.include(err.toString(), "TimelockNotExpired");
+      }
+
+      await new Promise((resolve) =>
+        setTimeout(resolve, (timelockDelay.toNumber() + 1) * 1000)
+      );
+
+      const freelancerBefore = await provider.connection.getBalance(
+        freelancer.publicKey
+      );
+      const recruiterBefore = await provider.connection.getBalance(
+        recruiter.publicKey
+      );
+      const sig = await execute();
+      const freelancerAfter = await provider.connection.getBalance(
+        freelancer.publicKey
+      );
+      const recruiterAfter = await provider.connection.getBalance(
+        recruiter.publicKey
+      );
+
+      const total = milestoneAmounts.reduce((a, b) => a.add(b), new BN(0));
+      const approved = milestoneAmounts[0].toNumber();
+      const fee = platformFee(milestoneAmounts[0]).toNumber();
+      assert.equal(freelancerAfter - freelancerBefore, approved - fee);
+      assert.equal(recruiterAfter - recruiterBefore, total.toNumber() - approved);
+
+      const [event] = await emittedEvents(sig);
+      assert.equal(event.name, "emergencyClose");
+      assert.equal(event.data.freelancerAmount.toNumber(), approved - fee);
+      assert.equal(
+        event.data.recruiterAmount.toNumber(),
+        total.toNumber() - approved
+      );
+      assert.equal(event.data.fee.toNumber(), fee);
+
+      assert.isNull(await provider.connection.getAccountInfo(proposal));
+      const escrowAccount = await program.account.escrow.fetch(
+        multisigEscrowPDA
+      );
+      assert.deepEqual(escrowAccount.status, { terminated: {} });
+      assert.equal(
+        escrowAccount.milestoneAmounts[0].toNumber(),
+        escrowAccount.milestoneClaimedAmounts[0].toNumber()
+      );
+      assert.equal(escrowAccount.milestoneAmounts[1].toNumber(), 0);
+    });
+
+    it("Slashes a ghosting freelancer's collateral to the recruiter", async () => {
+      const slashJobId = "slash-job";
+      const [slashEscrowPDA] = PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("escrow"),
+          recruiter.publicKey.toBuffer(),
+          hashJobId(slashJobId),
+          generationSeed(0),
+        ],
+        program.programId
+      );
+
+      await applyToJob(slashJobId, freelancer);
+      await program.methods
+        .createJobEscrow(
+          slashJobId,
+          freelancer.publicKey,
+          milestoneAmounts,
+          noDueDates(milestoneAmounts),
+          reviewPeriod,
+          jobTerms()
+        )
+        .accounts({
+          escrow: slashEscrowPDA,
+          jobCounter: jobCounterPDA(slashJobId),
+          recruiter: recruiter.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([recruiter])
+        .rpc();
+      await acceptJob(slashEscrowPDA);
+      await rollApplicationBond(slashJobId, slashEscrowPDA);
+
+      await flagEscrow(slashEscrowPDA);
+      const proposal = await passProposal(
+        { slashCollateral: { escrow: slashEscrowPDA } },
+        slashEscrowPDA
+      );
+
+      const recruiterBefore = await provider.connection.getBalance(
+        recruiter.publicKey
+      );
+      const sig = await program.methods
+        .slashCollateral()
+        .accounts({
+          escrow: slashEscrowPDA,
+          multisig: multisigPDA,
+          proposal,
+          recruiter: recruiter.publicKey,
+          proposer: owners[0].publicKey,
+        })
+        .signers([recruiter])
+        .rpc({ commitment: "confirmed" });
+      const recruiterAfter = await provider.connection.getBalance(
+        recruiter.publicKey
+      );
+      assert.equal(recruiterAfter - recruiterBefore, applicationBond.toNumber());
+
+      const [event] = await emittedEvents(sig);
+      assert.equal(event.name, "collateralSettled");
+      assert.isTrue(event.data.slashed);
+      assert.equal(
+        event.data.amount.toString(),
+        applicationBond.toString()
+      );
+
+      const escrowAccount = await program.account.escrow.fetch(slashEscrowPDA);
+      assert.equal(escrowAccount.collateral.toNumber(), 0);
+      assert.deepEqual(escrowAccount.status, { flagged: {} });
+    });
+
+    it("Rejects a fee above the cap", async () => {
+      const { proposalCount } = await program.account.multisig.fetch(
+        multisigPDA
+      );
+      try {
+        await program.methods
+          .proposePlatformAction({ setFees: { treasury, feeBps: 1_001 } })
+          .accounts({
+            multisig: multisigPDA,
+            proposal: proposalPDA(proposalCount.toNumber()),
+            escrow: null,
+            proposer: owners[0].publicKey,
+          })
+          .signers([owners[0]])
+          .rpc();
+
+        assert.fail("Should have rejected fee above 1000 bps");
+      } catch (err) {
+        assert.include(err.toString(), "InvalidFeeBps");
+      }
+    });
+
+    it("Fee changes only apply to new escrows", async () => {
+      const setFees = async (newFeeBps: number) => {
+        const proposal = await passProposal({
+          setFees: { treasury, feeBps: newFeeBps },
+        });
+        await program.methods
+          .setFees()
+          .accounts({
+            config: configPDA,
+            multisig: multisigPDA,
+            proposal,
+            proposer: owners[0].publicKey,
+          })
+          .rpc();
+      };
+
+      await setFees(500);
+      const config = await program.account.config.fetch(configPDA);
+      assert.equal(config.feeBps, 500);
+      const escrowAccount = await program.account.escrow.fetch(escrowPDA);
+      assert.equal(escrowAccount.feeBps, feeBps);
+
+      await setFees(feeBps);
+    });
+
+    it("Withdraws only accrued fees after multisig approval", async () => {
+      const ledger = await program.account.treasury.fetch(feeTreasuryPDA);
+      assert.isAbove(ledger.accruedLamports.toNumber(), 0);
+      const withdraw = (proposal: PublicKey) =>
+        program.methods
+          .withdrawFees()
+          .accounts({
+            config: configPDA,
+            feeTreasury: feeTreasuryPDA,
+            treasury,
+            multisig: multisigPDA,
+            proposal,
+            proposer: owners[0].publicKey,
+          })
+          .rpc();
+
+      const tooMuch = await passProposal({
+        withdrawFees: { mint: null, amount: ledger.accruedLamports.addn(1) },
+      });
+      try {
+        await withdraw(tooMuch);
+        assert.fail("Should not withdraw more than the accrued fees");
+      } catch (err) {
+        assert.include(err.toString(), "InsufficientAccruedFees");
+      }
+
+      const proposal = await passProposal({
+        withdrawFees: { mint: null, amount: ledger.accruedLamports },
+      });
+      const before = await provider.connection.getBalance(treasury);
+      await withdraw(proposal);
+      const after = await provider.connection.getBalance(treasury);
+      assert.equal(after - before, ledger.accruedLamports.toNumber());
+
+      const drained = await program.account.treasury.fetch(feeTreasuryPDA);
+      assert.equal(drained.accruedLamports.toNumber(), 0);
+    });
+
+    it("Emergency-closes a disputed escrow and refunds the dispute bond", async () => {
+      const jobId = "emergency-dispute-job";
+      const [disputedEscrowPDA] = PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("escrow"),
+          recruiter.publicKey.toBuffer(),
+          hashJobId(jobId),
+          generationSeed(0),
+        ],
+        program.programId
+      );
+      const [disputePDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("dispute"), disputedEscrowPDA.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .createJobEscrow(
+          jobId,
+          freelancer.publicKey,
+          milestoneAmounts,
+          noDueDates(milestoneAmounts),
+          reviewPeriod,
+          jobTerms()
+        )
+        .accounts({
+          escrow: disputedEscrowPDA,
+          jobCounter: jobCounterPDA(jobId),
+          recruiter: recruiter.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([recruiter])
+        .rpc();
+      await acceptJob(disputedEscrowPDA);
+      await program.methods
+        .openDispute(Array.from(hashJobId("evidence")))
+        .accounts({
+          escrow: disputedEscrowPDA,
+          dispute: disputePDA,
+          config: configPDA,
+          disputant: freelancer.publicKey,
+        })
+        .signers([freelancer])
+        .rpc();
+
+      const proposal = await passProposal(
+        { emergencyClose: { escrow: disputedEscrowPDA } },
+        disputedEscrowPDA
+      );
+      const execute = (withDispute: boolean) =>
+        program.methods
+          .platformEmergencyClose()
+          .accounts({
+            escrow: disputedEscrowPDA,
+            multisig: multisigPDA,
+            proposal,
+            feeTreasury: feeTreasuryPDA,
+            dispute: withDispute ? disputePDA : null,
+            freelancer: freelancer.publicKey,
+            recruiter: recruiter.publicKey,
+            openedBy: withDispute ? freelancer.publicKey : null,
+            proposer: owners[0].publicKey,
+          })
+          .rpc();
+
+      try {
+        await execute(false);
+        assert.fail("Should require the dispute account");
+      } catch (err) {
+        assert.include(err.toString(), "DisputeAccountRequired");
+      }
+
+      const freelancerBefore = await provider.connection.getBalance(
+        freelancer.publicKey
+      );
+      await execute(true);
+      const freelancerAfter = await provider.connection.getBalance(
+        freelancer.publicKey
+      );
+
+      // Nothing was approved, so the freelancer only gets the bond and rent back
+      assert.isAtLeast(
+        freelancerAfter - freelancerBefore,
+        disputeBond.toNumber()
+      );
+      assert.isNull(await provider.connection.getAccountInfo(disputePDA));
+      const escrowAccount = await program.account.escrow.fetch(
+        disputedEscrowPDA
+      );
+      assert.deepEqual(escrowAccount.status, { terminated: {} });
+    });
+
+    it("Rotates the owner set and retires the old owners' proposals", async () => {
+      const newOwner = Keypair.generate();
+      const sig = await provider.connection.requestAirdrop(
+        newOwner.publicKey,
+        LAMPORTS_PER_SOL
+      );
+      await provider.connection.confirmTransaction(sig);
+
+      const rotate = async (nextOwners: PublicKey[], approver: Keypair) => {
+        const proposal = await nextProposalPDA();
+        await program.methods
+          .proposePlatformAction({
+            setOwners: { owners: nextOwners, threshold: 2 },
+          })
+          .accounts({
+            multisig: multisigPDA,
+            proposal,
+            escrow: null,
+            proposer: owners[0].publicKey,
+          })
+          .signers([owners[0]])
+          .rpc();
+        await program.methods
+          .approveProposal()
+          .accounts({
+            multisig: multisigPDA,
+            proposal,
+            owner: approver.publicKey,
+          })
+          .signers([approver])
+          .rpc();
+        // Owner changes are not held back by the notice period
+        await program.methods
+          .setMultisigOwners()
+          .accounts({
+            multisig: multisigPDA,
+            proposal,
+            proposer: owners[0].publicKey,
+          })
+          .rpc();
+      };
+
+      // Fully approved under the old owner set, but not yet executed
+      const staleProposal = await passProposal({
+        setFees: { treasury, feeBps: 500 },
+      });
+      const { ownerSetSeqno } = await program.account.multisig.fetch(
+        multisigPDA
+      );
+
+      await rotate(
+        [owners[0].publicKey, owners[1].publicKey, newOwner.publicKey],
+        owners[1]
+      );
+      let multisig = await program.account.multisig.fetch(multisigPDA);
+      assert.deepEqual(
+        multisig.owners.map((owner) => owner.toBase58()),
+        [owners[0], owners[1], newOwner].map((owner) =>
+          owner.publicKey.toBase58()
+        )
+      );
+      assert.equal(multisig.ownerSetSeqno, ownerSetSeqno + 1);
+
+      try {
+        await program.methods
+          .setFees()
+          .accounts({
+            config: configPDA,
+            multisig: multisigPDA,
+            proposal: staleProposal,
+            proposer: owners[0].publicKey,
+          })
+          .rpc();
+        assert.fail("Should not execute a proposal from the old owner set");
+      } catch (err) {
+        assert.include(err.toString(), "StaleProposal");
+      }
+      const config = await program.account.config.fetch(configPDA);
+      assert.equal(config.feeBps, feeBps);
+
+      const proposal = await nextProposalPDA();
+      await program.methods
+        .proposePlatformAction({ setFees: { treasury, feeBps: 500 } })
+        .accounts({
+          multisig: multisigPDA,
+          proposal,
+          escrow: null,
+          proposer: owners[0].publicKey,
+        })
+        .signers([owners[0]])
+        .rpc();
+      try {
+        await program.methods
+          .approveProposal()
+          .accounts({
+            multisig: multisigPDA,
+            proposal,
+            owner: owners[2].publicKey,
+          })
+          .signers([owners[2]])
+          .rpc();
+        assert.fail("A removed owner should not be able to approve");
+      } catch (err) {
+        assert.include(err.toString(), "NotMultisigOwner");
+      }
+      await program.methods
+        .cancelProposal()
+        .accounts({
+          multisig: multisigPDA,
+          proposal,
+          proposer: owners[0].publicKey,
+          owner: newOwner.publicKey,
+        })
+        .signers([newOwner])
+        .rpc();
+
+      // Later suites rely on the original owners
+      await rotate(
+        owners.map((owner) => owner.publicKey),
+        newOwner
+      );
+      multisig = await program.account.multisig.fetch(multisigPDA);
+      assert.equal(multisig.ownerSetSeqno, ownerSetSeqno + 2);
+    });
+  });
+
+  describe("disputes", () => {
+    const disputeJobId = "dispute-job";
+    const [disputeEscrowPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("escrow"),
+        recruiter.publicKey.toBuffer(),
+        hashJobId(disputeJobId),
+        generationSeed(0),
+      ],
+      program.programId
+    );
+    const [disputePDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("dispute"), disputeEscrowPDA.toBuffer()],
+      program.programId
+    );
+
+    before(async () => {
+      await program.methods
+        .createJobEscrow(
+          disputeJobId,
+          freelancer.publicKey,
+          milestoneAmounts,
+          noDueDates(milestoneAmounts),
+          reviewPeriod,
+          jobTerms()
+        )
+        .accounts({
+          escrow: disputeEscrowPDA,
+          jobCounter: jobCounterPDA(disputeJobId),
+          recruiter: recruiter.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([recruiter])
+        .rpc();
+
+      await acceptJob(disputeEscrowPDA);
+    });
+
+    it("Prevents outsiders from opening a dispute", async () => {
+      const outsider = Keypair.generate();
+
+      try {
+        await program.methods
+          .openDispute(Array(32).fill(0))
+          .accounts({
+            escrow: disputeEscrowPDA,
+            dispute: disputePDA,
+            config: configPDA,
+            disputant: outsider.publicKey,
+          })
+          .signers([outsider])
+          .rpc();
+
+        assert.fail("Should have rejected outsider dispute");
+      } catch (err) {
+        assert.include(err.toString(), "NotEscrowParty");
+      }
+    });
+
+    it("Freelancer opens a dispute and posts the bond", async () => {
+      await program.methods
+        .openDispute(Array.from(hashJobId("evidence")))
+        .accounts({
+          escrow: disputeEscrowPDA,
+          dispute: disputePDA,
+          config: configPDA,
+          disputant: freelancer.publicKey,
+        })
+        .signers([freelancer])
+        .rpc();
+
+      const dispute = await program.account.dispute.fetch(disputePDA);
+      assert.equal(dispute.openedBy.toBase58(), freelancer.publicKey.toBase58());
+      assert.equal(
+        dispute.arbitrator.toBase58(),
+        arbitrator.publicKey.toBase58()
+      );
+      assert.equal(dispute.bond.toString(), disputeBond.toString());
+
+      const escrowAccount = await program.account.escrow.fetch(disputeEscrowPDA);
+      assert.deepEqual(escrowAccount.status, { disputed: {} });
+    });
+
+    it("Freezes approvals while disputed", async () => {
+      try {
+        await program.methods
+          .approveMilestone(0, milestoneAmounts[0])
+          .accounts({
+            escrow: disputeEscrowPDA,
+            recruiter: recruiter.publicKey,
+          })
+          .signers([recruiter])
+          .rpc();
+
+        assert.fail("Should have frozen approvals");
+      } catch (err) {
+        assert.include(err.toString(), "EscrowDisputed");
+      }
+    });
+
+    it("Prevents non-arbitrator from resolving", async () => {
+      try {
+        await program.methods
+          .resolveDispute([new BN(0), new BN(0), new BN(0)], true)
+          .accounts({
+            escrow: disputeEscrowPDA,
+            dispute: disputePDA,
+            config: configPDA,
+            recruiter: recruiter.publicKey,
+            freelancer: freelancer.publicKey,
+            openedBy: freelancer.publicKey,
+            treasury,
+            arbitrator: recruiter.publicKey,
+          })
+          .signers([recruiter])
+          .rpc();
+
+        assert.fail("Should have rejected non-arbitrator");
+      } catch (err) {
+        assert.include(err.toString(), "UnauthorizedArbitrator");
+      }
+    });
+
+    it("Arbitrator splits funds per milestone", async () => {
+      const freelancerBefore = await provider.connection.getBalance(
+        freelancer.publicKey
+      );
+      const award = [
+        milestoneAmounts[0],
+        milestoneAmounts[1].divn(2),
+        new BN(0),
+      ];
+
+      await program.methods
+        .resolveDispute(award, true)
+        .accounts({
+          escrow: disputeEscrowPDA,
+          dispute: disputePDA,
+          config: configPDA,
+          recruiter: recruiter.publicKey,
+          freelancer: freelancer.publicKey,
+          openedBy: freelancer.publicKey,
+          treasury,
+          arbitrator: arbitrator.publicKey,
+        })
+        .signers([arbitrator])
+        .rpc();
+
+      const freelancerAfter = await provider.connection.getBalance(
+        freelancer.publicKey
+      );
+      const expected = award
+        .reduce((a, b) => a.add(b), new BN(0))
+        .add(disputeBond)
+        .toNumber();
+      // Freelancer also receives the dispute account rent back
+      assert.isAtLeast(freelancerAfter - freelancerBefore, expected);
+      assert.isNull(await provider.connection.getAccountInfo(disputePDA));
+
+      const escrowAccount = await program.account.escrow.fetch(disputeEscrowPDA);
+      assert.deepEqual(escrowAccount.status, { completed: {} });
+
+      await program.methods
+        .closeCompletedEscrow()
+        .accounts({ escrow: disputeEscrowPDA, recruiter: recruiter.publicKey })
+        .rpc();
+      assert.isNull(await provider.connection.getAccountInfo(disputeEscrowPDA));
+    });
+  });
+
+  describe("token escrows", () => {
+    const decimals = 6;
+    const tokenAmounts = [new BN(100_000_000), new BN(250_000_000)];
+    let mint: PublicKey;
+    let recruiterTokenAccount: PublicKey;
+
+    const tokenEscrowAccounts = (tokenJobId: string) => {
+      const [escrow] = PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("escrow"),
+          recruiter.publicKey.toBuffer(),
+          hashJobId(tokenJobId),
+          generationSeed(0),
+        ],
+        program.programId
+      );
+      const [vault] = PublicKey.findProgramAddressSync(
+        [Buffer.from("token_vault"), escrow.toBuffer()],
+        program.programId
+      );
+      return { escrow, vault };
+    };
+    const acceptTokenJob = (escrow: PublicKey, vault: PublicKey) =>
+      program.methods
+        .acceptTokenJob(termsHash)
+        .accounts({
+          escrow,
+          config: configPDA,
+          feeTreasury: feeTreasuryPDA,
+          mint,
+          vault,
+          freelancerTokenAccount: getAssociatedTokenAddressSync(
+            mint,
+            freelancer.publicKey,
+            false,
+            TOKEN_2022_PROGRAM_ID
+          ),
+          feeTreasuryTokenAccount: getAssociatedTokenAddressSync(
+            mint,
+            feeTreasuryPDA,
+            true,
+            TOKEN_2022_PROGRAM_ID
+          ),
+          freelancer: freelancer.publicKey,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .signers([freelancer])
+        .rpc();
+
+    before(async () => {
+      mint = await createMint(
+        provider.connection,
+        recruiter,
+        recruiter.publicKey,
+        null,
+        decimals,
+        undefined,
+        undefined,
+        TOKEN_2022_PROGRAM_ID
+      );
+      recruiterTokenAccount = (
+        await getOrCreateAssociatedTokenAccount(
+          provider.connection,
+          recruiter,
+          mint,
+          recruiter.publicKey,
+          false,
+          undefined,
+          undefined,
+          TOKEN_2022_PROGRAM_ID
+        )
+      ).address;
+      await mintTo(
+        provider.connection,
+        recruiter,
+        mint,
+        recruiterTokenAccount,
+        recruiter,
+        1_000_000_000,
+        [],
+        undefined,
+        TOKEN_2022_PROGRAM_ID
+      );
+    });
+
+    it("Creates a token escrow and pays out to the freelancer ATA", async () => {
+      const tokenJobId = "token-job";
+      const { escrow, vault } = tokenEscrowAccounts(tokenJobId);
+
+      await program.methods
+        .createTokenJobEscrow(
+          tokenJobId,
+          freelancer.publicKey,
+          tokenAmounts,
+          noDueDates(tokenAmounts),
+          reviewPeriod,
+          jobTerms()
+        )
+        .accounts({
+          escrow,
+          mint,
+          vault,
+          recruiterTokenAccount,
+          jobCounter: jobCounterPDA(tokenJobId),
+          recruiter: recruiter.publicKey,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([recruiter])
+        .rpc();
+
+      await acceptTokenJob(escrow, vault);
+
+      const escrowAccount = await program.account.escrow.fetch(escrow);
+      assert.equal(escrowAccount.mint.toBase58(), mint.toBase58());
+      const vaultAccount = await getAccount(
+        provider.connection,
+        vault,
+        undefined,
+        TOKEN_2022_PROGRAM_ID
+      );
+      assert.equal(vaultAccount.amount.toString(), "350000000");
+
+      await program.methods
+        .approveMilestone(0, tokenAmounts[0])
+        .accounts({ escrow, recruiter: recruiter.publicKey })
+        .signers([recruiter])
+        .rpc();
+
+      const freelancerTokenAccount = getAssociatedTokenAddressSync(
+        mint,
+        freelancer.publicKey,
+        false,
+        TOKEN_2022_PROGRAM_ID
+      );
+      const feeTreasuryTokenAccount = getAssociatedTokenAddressSync(
+        mint,
+        feeTreasuryPDA,
+        true,
+        TOKEN_2022_PROGRAM_ID
+      );
+      await program.methods
+        .claimTokenMilestone(0)
+        .accounts({
+          escrow,
+          config: configPDA,
+          feeTreasury: feeTreasuryPDA,
+          mint,
+          vault,
+          freelancerTokenAccount,
+          feeTreasuryTokenAccount,
+          freelancer: freelancer.publicKey,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .signers([freelancer])
+        .rpc();
+
+      const freelancerAccount = await getAccount(
+        provider.connection,
+        freelancerTokenAccount,
+        undefined,
+        TOKEN_2022_PROGRAM_ID
+      );
+      const fee = platformFee(tokenAmounts[0]);
+      assert.equal(
+        freelancerAccount.amount.toString(),
+        tokenAmounts[0].sub(fee).toString()
+      );
+      const feeAccount = await getAccount(
+        provider.connection,
+        feeTreasuryTokenAccount,
+        undefined,
+        TOKEN_2022_PROGRAM_ID
+      );
+      assert.equal(feeAccount.amount.toString(), fee.toString());
+    });
+
+    it("Rejects SOL instructions on a token escrow", async () => {
+      const { escrow } = tokenEscrowAccounts("token-job");
+
+      try {
+        await program.methods
+          .claimMilestone(0)
+          .accounts({ escrow, freelancer: freelancer.publicKey })
+          .signers([freelancer])
+          .rpc();
+
+        assert.fail("Should have rejected SOL claim on token escrow");
+      } catch (err) {
+        assert.include(err.toString(), "InvalidEscrowMint");
+      }
+    });
+
+    it("Cancels a token escrow and refunds the recruiter", async () => {
+      const tokenJobId = "token-cancel-job";
+      const { escrow, vault } = tokenEscrowAccounts(tokenJobId);
+
+      await program.methods
+        .createTokenJobEscrow(
+          tokenJobId,
+          freelancer.publicKey,
+          tokenAmounts,
+          noDueDates(tokenAmounts),
+          reviewPeriod,
+          jobTerms()
+        )
+        .accounts({
+          escrow,
+          mint,
+          vault,
+          recruiterTokenAccount,
+          jobCounter: jobCounterPDA(tokenJobId),
+          recruiter: recruiter.publicKey,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([recruiter])
+        .rpc();
+
+      await acceptTokenJob(escrow, vault);
+
+      const before = await getAccount(
+        provider.connection,
+        recruiterTokenAccount,
+        undefined,
+        TOKEN_2022_PROGRAM_ID
+      );
+
+      await program.methods
+        .cancelTokenJob()
+        .accounts({
+          escrow,
+          mint,
+          vault,
+          recruiterTokenAccount,
+          recruiter: recruiter.publicKey,
+          freelancer: freelancer.publicKey,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .signers([recruiter, freelancer])
+        .rpc();
+
+      const after = await getAccount(
+        provider.connection,
+        recruiterTokenAccount,
+        undefined,
+        TOKEN_2022_PROGRAM_ID
+      );
+      assert.equal(
+        (after.amount - before.amount).toString(),
+        "350000000"
+      );
+      const escrowAccount = await program.account.escrow.fetch(escrow);
+      assert.deepEqual(escrowAccount.status, { cancelled: {} });
+
+      await program.methods
+        .closeCompletedTokenEscrow()
+        .accounts({
+          escrow,
+          mint,
+          vault,
+          recruiterTokenAccount,
+          recruiter: recruiter.publicKey,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .rpc();
+      assert.isNull(await provider.connection.getAccountInfo(vault));
+      assert.isNull(await provider.connection.getAccountInfo(escrow));
+    });
+
+    it("Refunds an unaccepted token escrow after the deadline", async () => {
+      const tokenJobId = "token-unaccepted-job";
+      const { escrow, vault } = tokenEscrowAccounts(tokenJobId);
+      const advance = new BN(50_000_000);
+
+      await program.methods
+        .createTokenJobEscrow(
+          tokenJobId,
+          freelancer.publicKey,
+          tokenAmounts,
+          noDueDates(tokenAmounts),
+          reviewPeriod,
+          jobTerms(advance, 2)
+        )
+        .accounts({
+          escrow,
+          mint,
+          vault,
+          recruiterTokenAccount,
+          jobCounter: jobCounterPDA(tokenJobId),
+          recruiter: recruiter.publicKey,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([recruiter])
+        .rpc();
+
+      const before = await getAccount(
+        provider.connection,
+        recruiterTokenAccount,
+        undefined,
+        TOKEN_2022_PROGRAM_ID
+      );
+      await new Promise((resolve) => setTimeout(resolve, 3000));
+
+      await program.methods
+        .refundUnacceptedTokenJob()
+        .accounts({
+          escrow,
+          mint,
+          vault,
+          recruiterTokenAccount,
+          recruiter: recruiter.publicKey,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .rpc();
+
+      const after = await getAccount(
+        provider.connection,
+        recruiterTokenAccount,
+        undefined,
+        TOKEN_2022_PROGRAM_ID
+      );
+      assert.equal((after.amount - before.amount).toString(), "400000000");
+      const escrowAccount = await program.account.escrow.fetch(escrow);
+      assert.deepEqual(escrowAccount.status, { cancelled: {} });
+    });
+
+    it("Rolls a token bond into collateral and keeps it out of refunds", async () => {
+      const tokenJobId = "token-bond-job";
+      const { escrow, vault } = tokenEscrowAccounts(tokenJobId);
+      const bond = new BN(10_000_000);
+      const application = applicationPDA(tokenJobId, freelancer.publicKey);
+      const [bondVault] = PublicKey.findProgramAddressSync(
+        [Buffer.from("bond_vault"), application.toBuffer()],
+        program.programId
+      );
+      const freelancerTokenAccount = getAssociatedTokenAddressSync(
+        mint,
+        freelancer.publicKey,
+        false,
+        TOKEN_2022_PROGRAM_ID
+      );
+      const tokenBalance = async (account: PublicKey) =>
+        (
+          await getAccount(
+            provider.connection,
+            account,
+            undefined,
+            TOKEN_2022_PROGRAM_ID
+          )
+        ).amount;
+
+      const apply = (amount: BN) =>
+        program.methods
+          .applyToTokenJob(tokenJobId, recruiter.publicKey, amount)
+          .accounts({
+            application,
+            mint,
+            config: configPDA,
+            bondVault,
+            freelancerTokenAccount,
+            freelancer: freelancer.publicKey,
+            tokenProgram: TOKEN_2022_PROGRAM_ID,
+          })
+          .signers([freelancer])
+          .rpc();
+
+      try {
+        await apply(bond);
+        assert.fail("Should only accept allow-listed bond mints");
+      } catch (err) {
+        assert.include(err.toString(), "BondMintNotAllowed");
+      }
+
+      await program.methods
+        .setBondMint(mint, bond)
+        .accounts({ config: configPDA, authority: provider.wallet.publicKey })
+        .rpc();
+      try {
+        await apply(bond.subn(1));
+        assert.fail("Should enforce the mint's minimum bond");
+      } catch (err) {
+        assert.include(err.toString(), "InsufficientApplicationBond");
+      }
+
+      await apply(bond);
+      assert.equal((await tokenBalance(bondVault)).toString(), bond.toString());
+
+      await program.methods
+        .createTokenJobEscrow(
+          tokenJobId,
+          freelancer.publicKey,
+          tokenAmounts,
+          noDueDates(tokenAmounts),
+          reviewPeriod,
+          jobTerms()
+        )
+        .accounts({
+          escrow,
+          mint,
+          vault,
+          recruiterTokenAccount,
+          jobCounter: jobCounterPDA(tokenJobId),
+          recruiter: recruiter.publicKey,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([recruiter])
+        .rpc();
+      await acceptTokenJob(escrow, vault);
+
+      await program.methods
+        .rollTokenApplicationBond()
+        .accounts({
+          escrow,
+          mint,
+          vault,
+          application,
+          bondVault,
+          recruiter: recruiter.publicKey,
+          freelancer: freelancer.publicKey,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .signers([recruiter])
+        .rpc();
+      assert.isNull(await provider.connection.getAccountInfo(bondVault));
+      let escrowAccount = await program.account.escrow.fetch(escrow);
+      assert.equal(escrowAccount.collateral.toString(), bond.toString());
+
+      const recruiterBefore = await tokenBalance(recruiterTokenAccount);
+      await program.methods
+        .cancelTokenJob()
+        .accounts({
+          escrow,
+          mint,
+          vault,
+          recruiterTokenAccount,
+          recruiter: recruiter.publicKey,
+          freelancer: freelancer.publicKey,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .signers([recruiter, freelancer])
+        .rpc();
+      const recruiterAfter = await tokenBalance(recruiterTokenAccount);
+      assert.equal((recruiterAfter - recruiterBefore).toString(), "350000000");
+      assert.equal((await tokenBalance(vault)).toString(), bond.toString());
+
+      const freelancerBefore = await tokenBalance(freelancerTokenAccount);
+      await program.methods
+        .releaseTokenCollateral()
+        .accounts({
+          escrow,
+          mint,
+          vault,
+          freelancerTokenAccount,
+          freelancer: freelancer.publicKey,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .rpc();
+      const freelancerAfter = await tokenBalance(freelancerTokenAccount);
+      assert.equal(
+        (freelancerAfter - freelancerBefore).toString(),
+        bond.toString()
+      );
+      escrowAccount = await program.account.escrow.fetch(escrow);
+      assert.equal(escrowAccount.collateral.toNumber(), 0);
+    });
+  });
+
+  describe("transfer-fee token escrows", () => {
+    const decimals = 6;
+    const transferFeeBps = 100;
+    const tokenAmounts = [new BN(100_000_000), new BN(250_000_000)];
+    const mintKeypair = Keypair.generate();
+    const mint = mintKeypair.publicKey;
+    let recruiterTokenAccount: PublicKey;
+
+    // Token-2022 withholds ceil(amount * bps / 10_000) in the destination
+    const transferFee = (amount: BN) =>
+      amount.muln(transferFeeBps).addn(9_999).divn(10_000);
+    // What a deposit must send for the vault to receive `net`
+    const grossUp = (net: BN) => {
+      const kept = 10_000 - transferFeeBps;
+      return net.add(transferFee(net.muln(10_000).addn(kept - 1).divn(kept)));
+    };
+    const tokenAccount = (owner: PublicKey) =>
+      getAssociatedTokenAddressSync(mint, owner, true, TOKEN_2022_PROGRAM_ID);
+    const tokenBalance = async (account: PublicKey) =>
+      (
+        await getAccount(
+          provider.connection,
+          account,
+          undefined,
+          TOKEN_2022_PROGRAM_ID
+        )
+      ).amount;
+    const createFeeEscrow = async (
+      tokenJobId: string,
+      dueDates = noDueDates(tokenAmounts)
+    ) => {
+      const [escrow] = PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("escrow"),
+          recruiter.publicKey.toBuffer(),
+          hashJobId(tokenJobId),
+          generationSeed(0),
+        ],
+        program.programId
+      );
+      const [vault] = PublicKey.findProgramAddressSync(
+        [Buffer.from("token_vault"), escrow.toBuffer()],
+        program.programId
+      );
+      await program.methods
+        .createTokenJobEscrow(
+          tokenJobId,
+          freelancer.publicKey,
+          tokenAmounts,
+          dueDates,
+          reviewPeriod,
+          jobTerms()
+        )
+        .accounts({
+          escrow,
+          mint,
+          vault,
+          recruiterTokenAccount,
+          jobCounter: jobCounterPDA(tokenJobId),
+          recruiter: recruiter.publicKey,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([recruiter])
+        .rpc();
+      return { escrow, vault };
+    };
+    const acceptFeeEscrow = (escrow: PublicKey, vault: PublicKey) =>
+      program.methods
+        .acceptTokenJob(termsHash)
+        .accounts({
+          escrow,
+          config: configPDA,
+          feeTreasury: feeTreasuryPDA,
+          mint,
+          vault,
+          freelancerTokenAccount: tokenAccount(freelancer.publicKey),
+          feeTreasuryTokenAccount: tokenAccount(feeTreasuryPDA),
+          freelancer: freelancer.publicKey,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .signers([freelancer])
+        .rpc();
+    const closeFeeEscrow = (escrow: PublicKey, vault: PublicKey) =>
+      program.methods
+        .closeCompletedTokenEscrow()
+        .accounts({
+          escrow,
+          mint,
+          vault,
+          recruiterTokenAccount,
+          recruiter: recruiter.publicKey,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .rpc();
+
+    before(async () => {
+      const mintLen = getMintLen([ExtensionType.TransferFeeConfig]);
+      await sendAndConfirmTransaction(
+        provider.connection,
+        new Transaction().add(
+          SystemProgram.createAccount({
+            fromPubkey: recruiter.publicKey,
+            newAccountPubkey: mint,
+            space: mintLen,
+            lamports:
+              await provider.connection.getMinimumBalanceForRentExemption(
+                mintLen
+              ),
+            programId: TOKEN_2022_PROGRAM_ID,
+          }),
+          createInitializeTransferFeeConfigInstruction(
+            mint,
+            recruiter.publicKey,
+            recruiter.publicKey,
+            transferFeeBps,
+            BigInt(1_000_000_000),
+            TOKEN_2022_PROGRAM_ID
+          ),
+          createInitializeMintInstruction(
+            mint,
+            decimals,
+            recruiter.publicKey,
+            null,
+            TOKEN_2022_PROGRAM_ID
+          )
+        ),
+        [recruiter, mintKeypair]
+      );
+      recruiterTokenAccount = (
+        await getOrCreateAssociatedTokenAccount(
+          provider.connection,
+          recruiter,
+          mint,
+          recruiter.publicKey,
+          false,
+          undefined,
+          undefined,
+          TOKEN_2022_PROGRAM_ID
+        )
+      ).address;
+      await mintTo(
+        provider.connection,
+        recruiter,
+        mint,
+        recruiterTokenAccount,
+        recruiter,
+        2_000_000_000,
+        [],
+        undefined,
+        TOKEN_2022_PROGRAM_ID
+      );
+    });
+
+    it("Funds, pays out and closes an escrow with withheld fees", async () => {
+      const { escrow, vault } = await createFeeEscrow("fee-mint-job");
+      assert.equal((await tokenBalance(vault)).toString(), "350000000");
+
+      const freelancerTokenAccount = tokenAccount(freelancer.publicKey);
+      const feeTreasuryTokenAccount = tokenAccount(feeTreasuryPDA);
+      await acceptFeeEscrow(escrow, vault);
+
+      for (const [index, amount] of tokenAmounts.entries()) {
+        await program.methods
+          .approveMilestone(index, amount)
+          .accounts({ escrow, recruiter: recruiter.publicKey })
+          .signers([recruiter])
+          .rpc();
+        await program.methods
+          .claimTokenMilestone(index)
+          .accounts({
+            escrow,
+            config: configPDA,
+            feeTreasury: feeTreasuryPDA,
+            mint,
+            vault,
+            freelancerTokenAccount,
+            feeTreasuryTokenAccount,
+            freelancer: freelancer.publicKey,
+            tokenProgram: TOKEN_2022_PROGRAM_ID,
+          })
+          .signers([freelancer])
+          .rpc();
+      }
+
+      const received = tokenAmounts
+        .map((amount) => amount.sub(platformFee(amount)))
+        .reduce((total, net) => total.add(net.sub(transferFee(net))), new BN(0));
+      assert.equal(
+        (await tokenBalance(freelancerTokenAccount)).toString(),
+        received.toString()
+      );
+      const escrowAccount = await program.account.escrow.fetch(escrow);
+      assert.deepEqual(escrowAccount.status, { completed: {} });
+
+      // The vault is empty but still holds the fee withheld on funding
+      await closeFeeEscrow(escrow, vault);
+      assert.isNull(await provider.connection.getAccountInfo(vault));
+      assert.isNull(await provider.connection.getAccountInfo(escrow));
+    });
+
+    it("Cancels and closes an escrow with withheld fees", async () => {
+      const { escrow, vault } = await createFeeEscrow("fee-mint-cancel-job");
+
+      const before = await tokenBalance(recruiterTokenAccount);
+      await program.methods
+        .cancelTokenJob()
+        .accounts({
+          escrow,
+          mint,
+          vault,
+          recruiterTokenAccount,
+          recruiter: recruiter.publicKey,
+          freelancer: null,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .signers([recruiter])
+        .rpc();
+      const after = await tokenBalance(recruiterTokenAccount);
+
+      const total = new BN(350_000_000);
+      assert.equal(
+        (after - before).toString(),
+        total.sub(transferFee(total)).toString()
+      );
+
+      await closeFeeEscrow(escrow, vault);
+      assert.isNull(await provider.connection.getAccountInfo(vault));
+      assert.isNull(await provider.connection.getAccountInfo(escrow));
+    });
+
+    it("Emergency-closes a flagged escrow and closes its vault", async () => {
+      const { escrow, vault } = await createFeeEscrow("fee-mint-emergency-job");
+      const freelancerTokenAccount = tokenAccount(freelancer.publicKey);
+      const feeTreasuryTokenAccount = tokenAccount(feeTreasuryPDA);
+      await acceptFeeEscrow(escrow, vault);
+      await program.methods
+        .approveMilestone(0, tokenAmounts[0])
+        .accounts({ escrow, recruiter: recruiter.publicKey })
+        .signers([recruiter])
+        .rpc();
+
+      await flagEscrow(escrow);
+      const proposal = await passProposal(
+        { emergencyClose: { escrow } },
+        escrow
+      );
+
+      const freelancerBefore = await tokenBalance(freelancerTokenAccount);
+      const treasuryBefore = await tokenBalance(feeTreasuryTokenAccount);
+      const recruiterBefore = await tokenBalance(recruiterTokenAccount);
+      await program.methods
+        .platformEmergencyCloseToken()
+        .accounts({
+          escrow,
+          mint,
+          vault,
+          dispute: null,
+          multisig: multisigPDA,
+          proposal,
+          feeTreasury: feeTreasuryPDA,
+          feeTreasuryTokenAccount,
+          freelancerTokenAccount,
+          recruiterTokenAccount,
+          freelancer: freelancer.publicKey,
+          recruiter: recruiter.publicKey,
+          openedBy: null,
+          proposer: owners[0].publicKey,
+          payer: provider.wallet.publicKey,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .rpc();
+
+      // Each payout loses the mint's transfer fee on the way out of the vault
+      const fee = platformFee(tokenAmounts[0]);
+      const freelancerNet = tokenAmounts[0].sub(fee);
+      const refund = tokenAmounts[1];
+      assert.equal(
+        (
+          (await tokenBalance(freelancerTokenAccount)) - freelancerBefore
+        ).toString(),
+        freelancerNet.sub(transferFee(freelancerNet)).toString()
+      );
+      assert.equal(
+        ((await tokenBalance(feeTreasuryTokenAccount)) - treasuryBefore).toString(),
+        fee.sub(transferFee(fee)).toString()
+      );
+      assert.equal(
+        ((await tokenBalance(recruiterTokenAccount)) - recruiterBefore).toString(),
+        refund.sub(transferFee(refund)).toString()
+      );
+
+      // No collateral was posted, so the vault and escrow close straight away
+      assert.isNull(await provider.connection.getAccountInfo(vault));
+      assert.isNull(await provider.connection.getAccountInfo(escrow));
+    });
+
+    it("Grosses up amendment and top-up deposits for the transfer fee", async () => {
+      const { escrow, vault } = await createFeeEscrow("fee-mint-amend-job");
+      await acceptFeeEscrow(escrow, vault);
+
+      const amend = (amounts: BN[]) =>
+        program.methods
+          .amendTokenEscrow(amounts, noDueDates(amounts))
+          .accounts({
+            escrow,
+            mint,
+            vault,
+            recruiterTokenAccount,
+            config: configPDA,
+            recruiter: recruiter.publicKey,
+            freelancer: freelancer.publicKey,
+            tokenProgram: TOKEN_2022_PROGRAM_ID,
+          })
+          .signers([recruiter, freelancer])
+          .rpc();
+
+      // Raising the total pulls the increase plus the fee withheld on it
+      const increase = new BN(50_000_000);
+      let before = await tokenBalance(recruiterTokenAccount);
+      await amend([tokenAmounts[0].add(increase), tokenAmounts[1]]);
+      let after = await tokenBalance(recruiterTokenAccount);
+      assert.equal((before - after).toString(), grossUp(increase).toString());
+      assert.equal((await tokenBalance(vault)).toString(), "400000000");
+
+      // Lowering it refunds the difference, less the fee on the way out
+      const decrease = new BN(30_000_000);
+      before = await tokenBalance(recruiterTokenAccount);
+      await amend([
+        tokenAmounts[0].add(increase),
+        tokenAmounts[1].sub(decrease),
+      ]);
+      after = await tokenBalance(recruiterTokenAccount);
+      assert.equal(
+        (after - before).toString(),
+        decrease.sub(transferFee(decrease)).toString()
+      );
+      assert.equal((await tokenBalance(vault)).toString(), "370000000");
+
+      const added = [new BN(80_000_000)];
+      before = await tokenBalance(recruiterTokenAccount);
+      await program.methods
+        .addTokenMilestones(added, noDueDates(added))
+        .accounts({
+          escrow,
+          mint,
+          vault,
+          recruiterTokenAccount,
+          config: configPDA,
+          recruiter: recruiter.publicKey,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .signers([recruiter])
+        .rpc();
+      after = await tokenBalance(recruiterTokenAccount);
+      assert.equal((before - after).toString(), grossUp(added[0]).toString());
+      assert.equal((await tokenBalance(vault)).toString(), "450000000");
+
+      const escrowAccount = await program.account.escrow.fetch(escrow);
+      assert.deepEqual(
+        escrowAccount.milestoneAmounts.map((n) => n.toString()),
+        ["150000000", "220000000", "80000000"]
+      );
+    });
+
+    it("Refunds remainders and expired milestones net of the transfer fee", async () => {
+      const now = Math.floor(Date.now() / 1000);
+      const { escrow, vault } = await createFeeEscrow("fee-mint-refund-job", [
+        new BN(now + 3),
+        new BN(0),
+      ]);
+      await acceptFeeEscrow(escrow, vault);
+
+      const approved = new BN(150_000_000);
+      await program.methods
+        .approveMilestone(1, approved)
+        .accounts({ escrow, recruiter: recruiter.publicKey })
+        .signers([recruiter])
+        .rpc();
+
+      const remainder = tokenAmounts[1].sub(approved);
+      let before = await tokenBalance(recruiterTokenAccount);
+      await program.methods
+        .refundTokenMilestoneRemainder(1)
+        .accounts({
+          escrow,
+          mint,
+          vault,
+          recruiterTokenAccount,
+          config: configPDA,
+          recruiter: recruiter.publicKey,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .signers([recruiter])
+        .rpc();
+      let after = await tokenBalance(recruiterTokenAccount);
+      assert.equal(
+        (after - before).toString(),
+        remainder.sub(transferFee(remainder)).toString()
+      );
+      assert.equal((await tokenBalance(vault)).toString(), "250000000");
+
+      // Milestone 0 lapses unsubmitted
+      await new Promise((resolve) => setTimeout(resolve, 5000));
+
+      before = await tokenBalance(recruiterTokenAccount);
+      await program.methods
+        .reclaimExpiredTokenMilestones()
+        .accounts({
+          escrow,
+          mint,
+          vault,
+          recruiterTokenAccount,
+          config: configPDA,
+          recruiter: recruiter.publicKey,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .signers([recruiter])
+        .rpc();
+      after = await tokenBalance(recruiterTokenAccount);
+      assert.equal(
+        (after - before).toString(),
+        tokenAmounts[0].sub(transferFee(tokenAmounts[0])).toString()
+      );
+      assert.equal(
+        (await tokenBalance(vault)).toString(),
+        approved.toString()
+      );
+    });
+
+    it("Resolves a dispute, paying both sides net of the transfer fee", async () => {
+      const { escrow, vault } = await createFeeEscrow("fee-mint-dispute-job");
+      await acceptFeeEscrow(escrow, vault);
+      const [dispute] = PublicKey.findProgramAddressSync(
+        [Buffer.from("dispute"), escrow.toBuffer()],
+        program.programId
+      );
+      await program.methods
+        .openDispute(Array.from(hashJobId("evidence")))
+        .accounts({
+          escrow,
+          dispute,
+          config: configPDA,
+          disputant: freelancer.publicKey,
+        })
+        .signers([freelancer])
+        .rpc();
+
+      const freelancerTokenAccount = tokenAccount(freelancer.publicKey);
+      const award = [new BN(60_000_000), new BN(0)];
+      const freelancerBefore = await tokenBalance(freelancerTokenAccount);
+      const recruiterBefore = await tokenBalance(recruiterTokenAccount);
+      await program.methods
+        .resolveTokenDispute(award, true)
+        .accounts({
+          escrow,
+          dispute,
+          config: configPDA,
+          mint,
+          vault,
+          recruiterTokenAccount,
+          freelancerTokenAccount,
+          recruiter: recruiter.publicKey,
+          freelancer: freelancer.publicKey,
+          openedBy: freelancer.publicKey,
+          treasury,
+          arbitrator: arbitrator.publicKey,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .signers([arbitrator])
+        .rpc();
+
+      const refund = new BN(350_000_000).sub(award[0]);
+      assert.equal(
+        (
+          (await tokenBalance(freelancerTokenAccount)) - freelancerBefore
+        ).toString(),
+        award[0].sub(transferFee(award[0])).toString()
+      );
+      assert.equal(
+        ((await tokenBalance(recruiterTokenAccount)) - recruiterBefore).toString(),
+        refund.sub(transferFee(refund)).toString()
+      );
+      assert.equal((await tokenBalance(vault)).toString(), "0");
+
+      await closeFeeEscrow(escrow, vault);
+      assert.isNull(await provider.connection.getAccountInfo(vault));
+    });
+
+    it("Slashes collateral that lost the transfer fee on every hop", async () => {
+      const tokenJobId = "fee-mint-slash-job";
+      const bond = new BN(10_000_000);
+      const application = applicationPDA(tokenJobId, freelancer.publicKey);
+      const [bondVault] = PublicKey.findProgramAddressSync(
+        [Buffer.from("bond_vault"), application.toBuffer()],
+        program.programId
+      );
+      const freelancerTokenAccount = tokenAccount(freelancer.publicKey);
+      await mintTo(
+        provider.connection,
+        recruiter,
+        mint,
+        freelancerTokenAccount,
+        recruiter,
+        bond.toNumber(),
+        [],
+        undefined,
+        TOKEN_2022_PROGRAM_ID
+      );
+      await program.methods
+        .setBondMint(mint, bond)
+        .accounts({ config: configPDA, authority: provider.wallet.publicKey })
+        .rpc();
+      await program.methods
+        .applyToTokenJob(tokenJobId, recruiter.publicKey, bond)
+        .accounts({
+          application,
+          mint,
+          config: configPDA,
+          bondVault,
+          freelancerTokenAccount,
+          freelancer: freelancer.publicKey,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .signers([freelancer])
+        .rpc();
+
+      const { escrow, vault } = await createFeeEscrow(tokenJobId);
+      await acceptFeeEscrow(escrow, vault);
+      await program.methods
+        .rollTokenApplicationBond()
+        .accounts({
+          escrow,
+          mint,
+          vault,
+          application,
+          bondVault,
+          recruiter: recruiter.publicKey,
+          freelancer: freelancer.publicKey,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .signers([recruiter])
+        .rpc();
+
+      // Withheld once into the bond vault and again into the escrow vault
+      const bonded = bond.sub(transferFee(bond));
+      const collateral = bonded.sub(transferFee(bonded));
+      let escrowAccount = await program.account.escrow.fetch(escrow);
+      assert.equal(escrowAccount.collateral.toString(), collateral.toString());
+
+      await flagEscrow(escrow);
+      const proposal = await passProposal(
+        { slashCollateral: { escrow } },
+        escrow
+      );
+      const before = await tokenBalance(recruiterTokenAccount);
+      await program.methods
+        .slashTokenCollateral()
+        .accounts({
+          escrow,
+          mint,
+          vault,
+          multisig: multisigPDA,
+          proposal,
+          recruiterTokenAccount,
+          recruiter: recruiter.publicKey,
+          proposer: owners[0].publicKey,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .signers([recruiter])
+        .rpc();
+      const after = await tokenBalance(recruiterTokenAccount);
+
+      assert.equal(
+        (after - before).toString(),
+        collateral.sub(transferFee(collateral)).toString()
+      );
+      escrowAccount = await program.account.escrow.fetch(escrow);
+      assert.equal(escrowAccount.collateral.toNumber(), 0);
+      assert.equal((await tokenBalance(vault)).toString(), "350000000");
+    });
+
+    it("Withdraws token fees net of the transfer fee", async () => {
+      const feeTreasuryTokenAccount = tokenAccount(feeTreasuryPDA);
+      const treasuryTokenAccount = (
+        await getOrCreateAssociatedTokenAccount(
+          provider.connection,
+          recruiter,
+          mint,
+          treasury,
+          false,
+          undefined,
+          undefined,
+          TOKEN_2022_PROGRAM_ID
+        )
+      ).address;
+      const accrued = new BN(
+        (await tokenBalance(feeTreasuryTokenAccount)).toString()
+      );
+      assert.isTrue(accrued.gtn(0));
+
+      const proposal = await passProposal({
+        withdrawFees: { mint, amount: accrued },
+      });
+      await program.methods
+        .withdrawTokenFees()
+        .accounts({
+          config: configPDA,
+          feeTreasury: feeTreasuryPDA,
+          mint,
+          feeTreasuryTokenAccount,
+          treasuryTokenAccount,
+          multisig: multisigPDA,
+          proposal,
+          proposer: owners[0].publicKey,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .rpc();
+
+      assert.equal(
+        (await tokenBalance(treasuryTokenAccount)).toString(),
+        accrued.sub(transferFee(accrued)).toString()
+      );
+      assert.equal((await tokenBalance(feeTreasuryTokenAccount)).toString(), "0");
+    });
+  });
+});
@@ -44,25 +44,79 @@ async function hashJobIdAsync(jobId: string): Promise<Uint8Array> {
     return new Uint8Array(hashBuffer);
 }
 
+/**
+ * Derive the PDA of a job's counter, which numbers the escrows funded for it
+ * @param recruiterPubkey - Public key of the recruiter
+ * @param jobId - Job ID string
+ * @returns Promise of [PDA PublicKey, bump seed]
+ */
+export async function deriveJobCounterPDA(
+    recruiterPubkey: PublicKey,
+    jobId: string
+): Promise<[PublicKey, number]> {
+    const jobIdHash = await hashJobIdAsync(jobId);
+
+    return PublicKey.findProgramAddressSync(
+        [
+            Buffer.from("job"),
+            recruiterPubkey.toBuffer(),
+            Buffer.from(jobIdHash),
+        ],
+        PROGRAM_ID
+    );
+}
+
+/**
+ * Read how many escrows have been funded for a job
+ * @param recruiterPubkey - Public key of the recruiter
+ * @param jobId - Job ID string
+ * @returns Escrow count, or null if the job has no counter yet
+ */
+export async function getJobEscrowCount(
+    recruiterPubkey: PublicKey,
+    jobId: string
+): Promise<number | null> {
+    const [jobCounterPDA] = await deriveJobCounterPDA(recruiterPubkey, jobId);
+    const accountInfo = await connection.getAccountInfo(jobCounterPDA);
+    if (!accountInfo) {
+        return null;
+    }
+
+    // 8-byte account discriminator, then escrow_count as a u32
+    return accountInfo.data.readUInt32LE(8);
+}
+
 /**
  * Derive the PDA (Program Derived Address) for an escrow account
  * IMPORTANT: Uses SHA-256 hash of job_id to match contract implementation
  * @param recruiterPubkey - Public key of the recruiter
- * @param jobId - Job ID string (max 50 characters)
+ * @param jobId - Job ID string
+ * @param generation - Escrow generation (u32). Defaults to the job's latest
+ * escrow, read from its counter. Jobs funded before counters existed have
+ * no generation seed.
  * @returns Promise of [PDA PublicKey, bump seed]
  */
 export async function deriveEscrowPDA(
     recruiterPubkey: PublicKey,
-    jobId: string
+    jobId: string,
+    generation?: number
 ): Promise<[PublicKey, number]> {
     const jobIdHash = await hashJobIdAsync(jobId);
 
+    if (generation === undefined) {
+        const escrowCount = await getJobEscrowCount(recruiterPubkey, jobId);
+        generation = escrowCount ? escrowCount - 1 : undefined;
+    }
+    const generationSeed = generation === undefined
+        ? []
+        : [new BN(generation).toArrayLike(Buffer, "le", 4)];
 
     return PublicKey.findProgramAddressSync(
         [
             Buffer.from("escrow"),
             recruiterPubkey.toBuffer(),
             Buffer.from(jobIdHash),
+            ...generationSeed,
         ],
         PROGRAM_ID
     );
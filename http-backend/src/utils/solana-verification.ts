@@ -25,19 +25,59 @@ async function hashJobIdAsync(jobId: string): Promise<Uint8Array> {
 }
 
 /**
- * Derive escrow PDA for a given recruiter and job ID
+ * Read how many escrows have been funded for a job from its counter PDA.
+ * Returns null if the job has no counter yet.
+ */
+async function getJobEscrowCount(
+    recruiterPubkey: PublicKey,
+    jobIdHash: Uint8Array
+): Promise<number | null> {
+    const [jobCounterPDA] = PublicKey.findProgramAddressSync(
+        [
+            Buffer.from("job"),
+            recruiterPubkey.toBuffer(),
+            Buffer.from(jobIdHash),
+        ],
+        PROGRAM_ID
+    );
+    const accountInfo = await connection.getAccountInfo(jobCounterPDA);
+    if (!accountInfo) {
+        return null;
+    }
+
+    // 8-byte account discriminator, then escrow_count as a u32
+    return accountInfo.data.readUInt32LE(8);
+}
+
+/**
+ * Derive escrow PDA for a given recruiter and job ID. Defaults to the job's
+ * latest escrow generation; jobs funded before job counters existed have no
+ * generation seed.
  */
 export async function deriveEscrowPDA(
     recruiterPubkey: PublicKey,
-    jobId: string
+    jobId: string,
+    generation?: number
 ): Promise<[PublicKey, number]> {
     const jobIdHash = await hashJobIdAsync(jobId);
 
+    if (generation === undefined) {
+        const escrowCount = await getJobEscrowCount(recruiterPubkey, jobIdHash);
+        generation = escrowCount ? escrowCount - 1 : undefined;
+    }
+    const generationSeed: Buffer[] = [];
+    if (generation !== undefined) {
+        const seed = Buffer.alloc(4);
+        seed.writeUInt32LE(generation);
+        generationSeed.push(seed);
+    }
+
     return PublicKey.findProgramAddressSync(
         [
             Buffer.from("escrow"),
             recruiterPubkey.toBuffer(),
             Buffer.from(jobIdHash),
+            ...generationSeed,
         ],
         PROGRAM_ID
     );